@@ -0,0 +1,38 @@
+use crate::database::service::{CgroupHistoryRecord, DatabaseService};
+use crate::error::{DashError, DashResult};
+use crate::metrics::cgroup::{CgroupMonitor, CgroupNode};
+use crate::metrics::pressure::{self, PressureStats};
+use tauri::State;
+
+const HISTORY_DEFAULT_LIMIT: u32 = 20_000;
+const HISTORY_MAX_LIMIT: u32 = 200_000;
+
+// 速率为与上一次调用之间的差值；max_depth 限制展开层数，根为第 0 层
+#[tauri::command]
+pub fn get_cgroup_tree(monitor: State<'_, CgroupMonitor>, max_depth: Option<u32>) -> DashResult<CgroupNode> {
+    monitor.tree(max_depth)
+}
+
+// 不指定 cgroup 时返回系统级的 /proc/pressure
+#[tauri::command]
+pub fn get_pressure(cgroup: Option<String>) -> DashResult<PressureStats> {
+    match cgroup {
+        Some(path) => pressure::read_cgroup(&path),
+        None => pressure::read_system(),
+    }
+}
+
+#[tauri::command]
+pub fn get_cgroup_history(
+    db: State<'_, DatabaseService>,
+    path: String,
+    start: i64,
+    end: i64,
+    limit: Option<u32>,
+) -> DashResult<Vec<CgroupHistoryRecord>> {
+    if end <= start {
+        return Err(DashError::InvalidArgument("end must be greater than start".to_string()));
+    }
+    let limit = limit.unwrap_or(HISTORY_DEFAULT_LIMIT).clamp(1, HISTORY_MAX_LIMIT);
+    Ok(db.get_cgroup_history(&path, start, end, limit)?)
+}
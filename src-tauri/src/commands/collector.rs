@@ -0,0 +1,17 @@
+use crate::error::DashResult;
+use crate::metrics::collector::{CollectorConfig, CollectorState};
+use tauri::State;
+
+#[tauri::command]
+pub fn get_collector_config(state: State<'_, CollectorState>) -> DashResult<CollectorConfig> {
+    Ok(state.config())
+}
+
+#[tauri::command]
+pub fn set_collector_config(
+    state: State<'_, CollectorState>,
+    config: CollectorConfig,
+) -> DashResult<CollectorConfig> {
+    state.set_config(&config)?;
+    Ok(state.config())
+}
@@ -1,50 +1,18 @@
 use crate::database::service::{
-    CpuHistoryRecord, DatabaseService, DiskHistoryRecord, MemoryHistoryRecord,
-    NetworkHistoryRecord, HourlyStats, DailyStats,
+    CpuCoreRecord, CpuHistoryRecord, DatabaseService, DiskHistoryRecord, MemoryHistoryRecord,
+    NetworkHistoryRecord, HourlyStats, DailyStats, InterfaceTrafficStats, ProcessHistorySeries,
+    ResourcePressureStats,
 };
+use crate::database::query::{HistoryQuery, HistoryQueryResponse};
+use crate::database::retention::RetentionPolicy;
+use crate::database::timezone::AggregationTimezone;
+use crate::error::{DashError, DashResult};
 use tauri::State;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SaveHistoryRequest {
-    pub cpu: Option<CpuHistoryInput>,
-    pub memory: Option<MemoryHistoryInput>,
-    pub network: Option<NetworkHistoryInput>,
-    pub disk: Option<DiskHistoryInput>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CpuHistoryInput {
-    pub usage: f32,
-    pub frequency: u64,
-    pub per_core: Vec<f32>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct MemoryHistoryInput {
-    pub usage_percent: f32,
-    pub used_bytes: u64,
-    pub available_bytes: u64,
-    pub swap_used_bytes: u64,
-    pub swap_total_bytes: u64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct NetworkHistoryInput {
-    pub interface_name: String,
-    pub rx_bytes: u64,
-    pub tx_bytes: u64,
-    pub rx_speed: f64,
-    pub tx_speed: f64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct DiskHistoryInput {
-    pub disk_name: String,
-    pub read_speed: f64,
-    pub write_speed: f64,
-    pub usage_percent: f32,
-}
+// 进程历史按原始行数限制返回量，默认约为 10 个进程一天的 5 秒采样
+const PROCESS_HISTORY_DEFAULT_LIMIT: u32 = 20_000;
+const PROCESS_HISTORY_MAX_LIMIT: u32 = 200_000;
 
 #[derive(Debug, Serialize)]
 pub struct HistoryDataResponse {
@@ -58,89 +26,48 @@ pub struct HistoryDataResponse {
 pub struct StatsResponse {
     pub hourly: Vec<HourlyStats>,
     pub daily: Vec<DailyStats>,
+    pub hourly_network: Vec<InterfaceTrafficStats>,
+    pub daily_network: Vec<InterfaceTrafficStats>,
+    pub hourly_pressure: Vec<ResourcePressureStats>,
+    pub daily_pressure: Vec<ResourcePressureStats>,
 }
 
 #[tauri::command]
-pub fn save_history_data(
-    db: State<'_, DatabaseService>,
-    request: SaveHistoryRequest,
-) -> Result<String, String> {
-    let timestamp = chrono::Utc::now().timestamp();
-
-    if let Some(cpu) = &request.cpu {
-        let record = CpuHistoryRecord {
-            timestamp,
-            usage: cpu.usage,
-            frequency: cpu.frequency,
-            per_core: cpu.per_core.clone(),
-        };
-        db.insert_cpu_history(&record).map_err(|e| e.to_string())?;
-    }
-
-    if let Some(memory) = &request.memory {
-        let record = MemoryHistoryRecord {
-            timestamp,
-            usage_percent: memory.usage_percent,
-            used_bytes: memory.used_bytes,
-            available_bytes: memory.available_bytes,
-            swap_used_bytes: memory.swap_used_bytes,
-            swap_total_bytes: memory.swap_total_bytes,
-        };
-        db.insert_memory_history(&record).map_err(|e| e.to_string())?;
-    }
-
-    if let Some(network) = &request.network {
-        let record = NetworkHistoryRecord {
-            timestamp,
-            interface_name: network.interface_name.clone(),
-            rx_bytes: network.rx_bytes,
-            tx_bytes: network.tx_bytes,
-            rx_speed: network.rx_speed,
-            tx_speed: network.tx_speed,
-        };
-        db.insert_network_history(&record).map_err(|e| e.to_string())?;
-    }
-
-    if let Some(disk) = &request.disk {
-        let record = DiskHistoryRecord {
-            timestamp,
-            disk_name: disk.disk_name.clone(),
-            read_speed: disk.read_speed,
-            write_speed: disk.write_speed,
-            usage_percent: disk.usage_percent,
-        };
-        db.insert_disk_history(&record).map_err(|e| e.to_string())?;
-    }
-
-    Ok("History data saved successfully".to_string())
+pub fn get_cpu_history(db: State<'_, DatabaseService>, hours: u32) -> DashResult<Vec<CpuHistoryRecord>> {
+    Ok(db.get_cpu_history(hours)?)
 }
 
+// 每个核心的 CPU 时间占比，core 为空时返回所有核心
 #[tauri::command]
-pub fn get_cpu_history(db: State<'_, DatabaseService>, hours: u32) -> Result<Vec<CpuHistoryRecord>, String> {
-    db.get_cpu_history(hours).map_err(|e| e.to_string())
+pub fn get_cpu_core_history(
+    db: State<'_, DatabaseService>,
+    hours: u32,
+    core: Option<u32>,
+) -> DashResult<Vec<CpuCoreRecord>> {
+    Ok(db.get_cpu_core_history(hours, core)?)
 }
 
 #[tauri::command]
-pub fn get_memory_history(db: State<'_, DatabaseService>, hours: u32) -> Result<Vec<MemoryHistoryRecord>, String> {
-    db.get_memory_history(hours).map_err(|e| e.to_string())
+pub fn get_memory_history(db: State<'_, DatabaseService>, hours: u32) -> DashResult<Vec<MemoryHistoryRecord>> {
+    Ok(db.get_memory_history(hours)?)
 }
 
 #[tauri::command]
-pub fn get_network_history(db: State<'_, DatabaseService>, hours: u32) -> Result<Vec<NetworkHistoryRecord>, String> {
-    db.get_network_history(hours).map_err(|e| e.to_string())
+pub fn get_network_history(db: State<'_, DatabaseService>, hours: u32) -> DashResult<Vec<NetworkHistoryRecord>> {
+    Ok(db.get_network_history(hours)?)
 }
 
 #[tauri::command]
-pub fn get_disk_history(db: State<'_, DatabaseService>, hours: u32) -> Result<Vec<DiskHistoryRecord>, String> {
-    db.get_disk_history(hours).map_err(|e| e.to_string())
+pub fn get_disk_history(db: State<'_, DatabaseService>, hours: u32) -> DashResult<Vec<DiskHistoryRecord>> {
+    Ok(db.get_disk_history(hours)?)
 }
 
 #[tauri::command]
-pub fn get_all_history(db: State<'_, DatabaseService>, hours: u32) -> Result<HistoryDataResponse, String> {
-    let cpu = db.get_cpu_history(hours).map_err(|e| e.to_string())?;
-    let memory = db.get_memory_history(hours).map_err(|e| e.to_string())?;
-    let network = db.get_network_history(hours).map_err(|e| e.to_string())?;
-    let disk = db.get_disk_history(hours).map_err(|e| e.to_string())?;
+pub fn get_all_history(db: State<'_, DatabaseService>, hours: u32) -> DashResult<HistoryDataResponse> {
+    let cpu = db.get_cpu_history(hours)?;
+    let memory = db.get_memory_history(hours)?;
+    let network = db.get_network_history(hours)?;
+    let disk = db.get_disk_history(hours)?;
 
     Ok(HistoryDataResponse {
         cpu,
@@ -151,24 +78,103 @@ pub fn get_all_history(db: State<'_, DatabaseService>, hours: u32) -> Result<His
 }
 
 #[tauri::command]
-pub fn get_stats(db: State<'_, DatabaseService>, days: u32) -> Result<StatsResponse, String> {
-    let hourly = db.get_hourly_stats(days).map_err(|e| e.to_string())?;
-    let daily = db.get_daily_stats(days).map_err(|e| e.to_string())?;
+pub fn query_history(db: State<'_, DatabaseService>, query: HistoryQuery) -> DashResult<HistoryQueryResponse> {
+    let policy = db.get_retention_policy()?;
+    let resolved = query.resolve(&policy, chrono::Utc::now().timestamp())?;
+    let buckets = db.query_history(&resolved)?;
+
+    Ok(HistoryQueryResponse {
+        metric: resolved.metric,
+        tier: resolved.tier,
+        bucket_secs: resolved.bucket_secs,
+        buckets,
+    })
+}
+
+// 按 PID 或进程名（精确匹配）查询 [start, end) 内的进程历史，至少指定其中一个
+#[tauri::command]
+pub fn get_process_history(
+    db: State<'_, DatabaseService>,
+    pid: Option<u32>,
+    name: Option<String>,
+    start: i64,
+    end: i64,
+    limit: Option<u32>,
+) -> DashResult<Vec<ProcessHistorySeries>> {
+    let name = name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    if pid.is_none() && name.is_none() {
+        return Err(DashError::InvalidArgument("either pid or name must be provided".to_string()));
+    }
+    if end <= start {
+        return Err(DashError::InvalidArgument("end must be greater than start".to_string()));
+    }
+    let limit = limit
+        .unwrap_or(PROCESS_HISTORY_DEFAULT_LIMIT)
+        .clamp(1, PROCESS_HISTORY_MAX_LIMIT);
+    Ok(db.get_process_history(pid, name.as_deref(), start, end, limit)?)
+}
+
+#[tauri::command]
+pub fn get_stats(db: State<'_, DatabaseService>, days: u32) -> DashResult<StatsResponse> {
+    let hourly = db.get_hourly_stats(days)?;
+    let daily = db.get_daily_stats(days)?;
+    let hourly_network = db.get_hourly_network_stats(days)?;
+    let daily_network = db.get_daily_network_stats(days)?;
+    let hourly_pressure = db.get_hourly_pressure_stats(days)?;
+    let daily_pressure = db.get_daily_pressure_stats(days)?;
 
     Ok(StatsResponse {
         hourly,
         daily,
+        hourly_network,
+        daily_network,
+        hourly_pressure,
+        daily_pressure,
     })
 }
 
 #[tauri::command]
-pub fn cleanup_old_data(db: State<'_, DatabaseService>) -> Result<String, String> {
-    db.cleanup_old_data().map_err(|e| e.to_string())?;
-    Ok("Old data cleaned up successfully".to_string())
+pub fn cleanup_old_data(db: State<'_, DatabaseService>) -> DashResult<String> {
+    let report = db.cleanup_old_data()?;
+    Ok(format!(
+        "Old data cleaned up successfully: {} row(s) deleted, database is {} bytes",
+        report.deleted_rows, report.database_bytes
+    ))
+}
+
+#[tauri::command]
+pub fn get_retention_policy(db: State<'_, DatabaseService>) -> DashResult<RetentionPolicy> {
+    Ok(db.get_retention_policy()?)
+}
+
+#[tauri::command]
+pub fn set_retention_policy(db: State<'_, DatabaseService>, policy: RetentionPolicy) -> DashResult<RetentionPolicy> {
+    policy.validate()?;
+    db.set_retention_policy(&policy)?;
+    Ok(policy)
+}
+
+#[tauri::command]
+pub fn aggregate_hourly(db: State<'_, DatabaseService>) -> DashResult<String> {
+    let hours = db.aggregate_hourly_stats()?;
+    Ok(format!("Hourly stats aggregated for {} hour(s)", hours))
 }
 
 #[tauri::command]
-pub fn aggregate_hourly(db: State<'_, DatabaseService>) -> Result<String, String> {
-    db.aggregate_hourly_stats().map_err(|e| e.to_string())?;
-    Ok("Hourly stats aggregated successfully".to_string())
-}
\ No newline at end of file
+pub fn aggregate_daily(db: State<'_, DatabaseService>) -> DashResult<String> {
+    let days = db.aggregate_daily_stats()?;
+    Ok(format!("Daily stats aggregated for {} day(s)", days))
+}
+
+#[tauri::command]
+pub fn get_aggregation_timezone(db: State<'_, DatabaseService>) -> DashResult<String> {
+    let timezone = db.get_aggregation_timezone()?;
+    Ok(timezone.name())
+}
+
+#[tauri::command]
+pub fn set_aggregation_timezone(db: State<'_, DatabaseService>, timezone: String) -> DashResult<String> {
+    let timezone = AggregationTimezone::parse(&timezone)?;
+    db.set_aggregation_timezone(timezone)?;
+    Ok(timezone.name())
+}
@@ -0,0 +1,39 @@
+use crate::error::DashResult;
+use crate::metrics::cpu::CpuMonitor;
+use crate::metrics::memory;
+use crate::metrics::publisher::{PublisherState, TickChannel};
+use crate::types::{CpuStats, MemoryDetails};
+use tauri::State;
+
+#[tauri::command]
+pub fn get_cpu_stats(monitor: State<'_, CpuMonitor>) -> DashResult<CpuStats> {
+    Ok(monitor.stats())
+}
+
+#[tauri::command]
+pub fn get_memory_details() -> DashResult<MemoryDetails> {
+    memory::read_details()
+}
+
+#[tauri::command]
+pub fn subscribe_metrics(
+    state: State<'_, PublisherState>,
+    interval_ms: u64,
+    on_tick: TickChannel,
+) -> DashResult<u64> {
+    state.subscribe(interval_ms, on_tick)
+}
+
+#[tauri::command]
+pub fn update_metrics_subscription(
+    state: State<'_, PublisherState>,
+    subscription_id: u64,
+    interval_ms: u64,
+) -> DashResult<()> {
+    state.update(subscription_id, interval_ms)
+}
+
+#[tauri::command]
+pub fn unsubscribe_metrics(state: State<'_, PublisherState>, subscription_id: u64) -> DashResult<bool> {
+    Ok(state.unsubscribe(subscription_id))
+}
@@ -1,3 +1,6 @@
+pub mod cgroup;
+pub mod collector;
 pub mod history;
+pub mod metrics;
 pub mod process;
 pub mod system_info;
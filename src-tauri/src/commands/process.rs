@@ -1,33 +1,277 @@
+use crate::database::service::{ProcessActionRecord, WatchTriggerRecord};
+use crate::database::DatabaseService;
+use crate::error::{DashError, DashResult};
+use crate::process::affinity::{self, AffinityInfo};
+use crate::process::audit::{ActionOutcome, PendingAction, DEFAULT_QUERY_LIMIT, MAX_QUERY_LIMIT};
+use crate::process::monitor::ProcessMonitor;
+use crate::process::priority::{self, IoClass, PriorityInfo};
+use crate::process::safety::{self, Guard};
+use crate::process::signal::{
+  self, Signal, TerminateReport, TreeKillReport, DEFAULT_GRACE_PERIOD_MS, MAX_GRACE_PERIOD_MS,
+};
+use crate::process::suspend::{SuspendState, SuspendedProcess};
+use crate::process::watch::{self, WatchEngine, WatchRule};
+#[cfg(target_os = "linux")]
+use crate::process::tree;
+use crate::types::{ProcessDetails, ProcessInfo};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
 #[tauri::command]
-pub fn kill_process(pid: u32) -> Result<(), String> {
-  if pid == 0 {
-    return Err("invalid pid".to_string());
-  }
+pub fn list_processes(monitor: State<'_, ProcessMonitor>) -> DashResult<Vec<ProcessInfo>> {
+  monitor.list()
+}
+
+#[tauri::command]
+pub fn get_process_details(monitor: State<'_, ProcessMonitor>, pid: u32) -> DashResult<ProcessDetails> {
+  monitor.details(pid)
+}
+
+// 每次操作时重新读取名单和祖先进程链，设置修改后立即生效
+fn guard(db: &DatabaseService) -> DashResult<Guard> {
+  Ok(Guard::new(safety::load_protected_names(db)?))
+}
+
+#[tauri::command]
+pub fn kill_process(db: State<'_, DatabaseService>, pid: u32, force: Option<bool>) -> DashResult<()> {
+  let force = force.unwrap_or(false);
+  let action = PendingAction::begin("kill", pid, Some(Signal::Kill), force);
+  let result = guard(&db).and_then(|guard| signal::kill(pid, &guard, force));
+  action.finish(&db, &result);
+  result
+}
+
+#[tauri::command]
+pub fn kill_process_tree(
+  db: State<'_, DatabaseService>,
+  pid: u32,
+  signal: Option<Signal>,
+  force: Option<bool>,
+) -> DashResult<TreeKillReport> {
+  let signal = signal.unwrap_or(Signal::Kill);
+  let force = force.unwrap_or(false);
+  let action = PendingAction::begin("kill_tree", pid, Some(signal), force);
+
+  #[cfg(target_os = "linux")]
+  let result = guard(&db).and_then(|guard| tree::kill_tree(pid, signal, &guard, force));
+
+  #[cfg(not(target_os = "linux"))]
+  let result = Err(DashError::Unsupported(
+    "Process tree termination is only implemented on Linux".to_string(),
+  ));
+
+  action.finish(&db, &result);
+  result
+}
 
-  #[cfg(target_os = "windows")]
-  {
-    let status = std::process::Command::new("taskkill")
-      .args(["/PID", &pid.to_string(), "/T", "/F"])
-      .status()
-      .map_err(|e| e.to_string())?;
-    if status.success() {
-      Ok(())
-    } else {
-      Err(format!("taskkill failed: {status}"))
-    }
+#[tauri::command]
+pub fn send_signal(
+  db: State<'_, DatabaseService>,
+  pid: u32,
+  signal: Signal,
+  force: Option<bool>,
+) -> DashResult<()> {
+  let force = force.unwrap_or(false);
+  let action = PendingAction::begin("signal", pid, Some(signal), force);
+  let result = guard(&db)
+    .and_then(|guard| guard.check(pid, force))
+    .and_then(|_| signal::send(pid, signal));
+  action.finish(&db, &result);
+  result
+}
+
+// 会阻塞整个宽限期，放到阻塞线程池里执行，不占用异步运行时的工作线程
+#[tauri::command]
+pub async fn terminate_process(
+  app: AppHandle,
+  pid: u32,
+  grace_period_ms: Option<u64>,
+  force: Option<bool>,
+) -> DashResult<TerminateReport> {
+  let grace_period_ms = grace_period_ms.unwrap_or(DEFAULT_GRACE_PERIOD_MS);
+  if grace_period_ms > MAX_GRACE_PERIOD_MS {
+    return Err(DashError::InvalidArgument(format!(
+      "grace_period_ms must be at most {}",
+      MAX_GRACE_PERIOD_MS
+    )));
   }
+  let force = force.unwrap_or(false);
+  tauri::async_runtime::spawn_blocking(move || {
+    let db = app.state::<DatabaseService>();
+    let action = PendingAction::begin("terminate", pid, Some(Signal::Term), force);
+    let result = guard(&db)
+      .and_then(|guard| guard.check(pid, force))
+      .and_then(|_| signal::terminate(pid, Duration::from_millis(grace_period_ms)));
+    action.finish(&db, &result);
+    result
+  })
+  .await
+  .map_err(|e| DashError::CommandFailed(format!("terminate task failed: {}", e)))?
+}
+
+// 暂停（SIGSTOP）默认连同子进程一起，父进程先停，避免继续派生新的子进程
+#[tauri::command]
+pub fn suspend_process(
+  db: State<'_, DatabaseService>,
+  suspended: State<'_, SuspendState>,
+  pid: u32,
+  tree: Option<bool>,
+  force: Option<bool>,
+) -> DashResult<TreeKillReport> {
+  let force = force.unwrap_or(false);
+  let action = PendingAction::begin("suspend", pid, Some(Signal::Stop), force);
+  let result = guard(&db).and_then(|guard| suspended.suspend(pid, tree.unwrap_or(true), &guard, force));
+  action.finish(&db, &result);
+  result
+}
 
-  #[cfg(not(target_os = "windows"))]
-  {
-    let status = std::process::Command::new("kill")
-      .args(["-9", &pid.to_string()])
-      .status()
-      .map_err(|e| e.to_string())?;
-    if status.success() {
-      Ok(())
-    } else {
-      Err(format!("kill failed: {status}"))
-    }
+#[tauri::command]
+pub fn resume_process(
+  db: State<'_, DatabaseService>,
+  suspended: State<'_, SuspendState>,
+  pid: u32,
+  tree: Option<bool>,
+) -> DashResult<TreeKillReport> {
+  let action = PendingAction::begin("resume", pid, Some(Signal::Cont), false);
+  let result = suspended.resume(pid, tree.unwrap_or(true));
+  action.finish(&db, &result);
+  result
+}
+
+// 只列出由 dashsys 暂停且仍处于暂停状态的进程
+#[tauri::command]
+pub fn list_suspended_processes(suspended: State<'_, SuspendState>) -> Vec<SuspendedProcess> {
+  suspended.list()
+}
+
+#[tauri::command]
+pub fn resume_all_processes(
+  db: State<'_, DatabaseService>,
+  suspended: State<'_, SuspendState>,
+) -> DashResult<TreeKillReport> {
+  let action = PendingAction::begin("resume_all", 0, Some(Signal::Cont), false);
+  let result = Ok(TreeKillReport {
+    root: 0,
+    signal: Signal::Cont,
+    processes: suspended.resume_all(),
+  });
+  action.finish(&db, &result);
+  result
+}
+
+#[tauri::command]
+pub fn get_process_priority(pid: u32) -> DashResult<PriorityInfo> {
+  priority::get(pid)
+}
+
+// 调高 nice 值即降低优先级，给失控的编译任务“降级”而不必直接结束
+#[tauri::command]
+pub fn set_process_nice(
+  db: State<'_, DatabaseService>,
+  pid: u32,
+  nice: i32,
+  force: Option<bool>,
+) -> DashResult<PriorityInfo> {
+  let force = force.unwrap_or(false);
+  let action = PendingAction::begin("renice", pid, None, force);
+  let result = guard(&db)
+    .and_then(|guard| guard.check(pid, force))
+    .and_then(|_| priority::set_nice(pid, nice));
+  action.finish(&db, &result);
+  result
+}
+
+#[tauri::command]
+pub fn set_process_io_priority(
+  db: State<'_, DatabaseService>,
+  pid: u32,
+  class: IoClass,
+  level: Option<u8>,
+  force: Option<bool>,
+) -> DashResult<PriorityInfo> {
+  let force = force.unwrap_or(false);
+  let action = PendingAction::begin("ioprio", pid, None, force);
+  let result = guard(&db)
+    .and_then(|guard| guard.check(pid, force))
+    .and_then(|_| priority::set_io_priority(pid, class, level));
+  action.finish(&db, &result);
+  result
+}
+
+#[tauri::command]
+pub fn get_process_affinity(pid: u32, all_threads: Option<bool>) -> DashResult<AffinityInfo> {
+  affinity::get(pid, all_threads.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn set_process_affinity(
+  db: State<'_, DatabaseService>,
+  pid: u32,
+  cpus: Vec<usize>,
+  all_threads: Option<bool>,
+  force: Option<bool>,
+) -> DashResult<AffinityInfo> {
+  let force = force.unwrap_or(false);
+  let action = PendingAction::begin("set_affinity", pid, None, force);
+  let result = guard(&db)
+    .and_then(|guard| guard.check(pid, force))
+    .and_then(|_| affinity::set(pid, &cpus, all_threads.unwrap_or(false)));
+  action.finish(&db, &result);
+  result
+}
+
+// 按时间范围 [start, end) 查询操作记录，可按结果过滤，最新的在前
+#[tauri::command]
+pub fn get_process_actions(
+  db: State<'_, DatabaseService>,
+  start: i64,
+  end: i64,
+  outcome: Option<ActionOutcome>,
+  limit: Option<u32>,
+) -> DashResult<Vec<ProcessActionRecord>> {
+  if end <= start {
+    return Err(DashError::InvalidArgument("end must be greater than start".to_string()));
   }
+  let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).clamp(1, MAX_QUERY_LIMIT);
+  Ok(db.get_process_actions(start, end, outcome.map(ActionOutcome::as_str), limit)?)
+}
+
+#[tauri::command]
+pub fn get_protected_processes(db: State<'_, DatabaseService>) -> DashResult<Vec<String>> {
+  safety::load_protected_names(&db)
+}
+
+#[tauri::command]
+pub fn set_protected_processes(db: State<'_, DatabaseService>, names: Vec<String>) -> DashResult<Vec<String>> {
+  safety::save_protected_names(&db, &names)
+}
+
+#[tauri::command]
+pub fn get_watch_rules(engine: State<'_, WatchEngine>) -> DashResult<Vec<WatchRule>> {
+  Ok(engine.rules())
+}
+
+// 整体替换规则列表，任何一条不合法时都不保存
+#[tauri::command]
+pub fn set_watch_rules(
+  db: State<'_, DatabaseService>,
+  engine: State<'_, WatchEngine>,
+  rules: Vec<WatchRule>,
+) -> DashResult<Vec<WatchRule>> {
+  engine.set_rules(&db, rules)
 }
 
+#[tauri::command]
+pub fn get_watch_triggers(
+  db: State<'_, DatabaseService>,
+  start: i64,
+  end: i64,
+  rule_name: Option<String>,
+  limit: Option<u32>,
+) -> DashResult<Vec<WatchTriggerRecord>> {
+  if end <= start {
+    return Err(DashError::InvalidArgument("end must be greater than start".to_string()));
+  }
+  let limit = limit.unwrap_or(watch::DEFAULT_QUERY_LIMIT).clamp(1, watch::MAX_QUERY_LIMIT);
+  Ok(db.get_watch_triggers(start, end, rule_name.as_deref(), limit)?)
+}
@@ -1,3 +1,4 @@
+use crate::error::DashResult;
 use serde::{Deserialize, Serialize};
 use std::process::Command;
 
@@ -29,7 +30,7 @@ pub struct NetworkAdapterInfo {
 }
 
 #[tauri::command]
-pub async fn get_detailed_system_info() -> Result<DetailedSystemInfo, String> {
+pub async fn get_detailed_system_info() -> DashResult<DetailedSystemInfo> {
     #[cfg(target_os = "windows")]
     {
         get_windows_system_info()
@@ -44,12 +45,14 @@ pub async fn get_detailed_system_info() -> Result<DetailedSystemInfo, String> {
     }
     #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     {
-        Err("Not implemented for this OS yet".to_string())
+        Err(crate::error::DashError::Unsupported("Not implemented for this OS yet".to_string()))
     }
 }
 
 #[cfg(target_os = "macos")]
-fn get_mac_system_info() -> Result<DetailedSystemInfo, String> {
+fn get_mac_system_info() -> DashResult<DetailedSystemInfo> {
+    use crate::error::DashError;
+
     // 1. Get Software Info
     let output = Command::new("system_profiler")
         .args([
@@ -59,18 +62,17 @@ fn get_mac_system_info() -> Result<DetailedSystemInfo, String> {
             "-json",
         ])
         .output()
-        .map_err(|e| format!("Failed to execute system_profiler: {}", e))?;
+        .map_err(|e| DashError::CommandFailed(format!("Failed to execute system_profiler: {}", e)))?;
 
     if !output.status.success() {
-        return Err(format!(
+        return Err(DashError::CommandFailed(format!(
             "system_profiler failed: {}",
             String::from_utf8_lossy(&output.stderr)
-        ));
+        )));
     }
 
     let json_str = String::from_utf8_lossy(&output.stdout);
-    let value: serde_json::Value =
-        serde_json::from_str(&json_str).map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    let value: serde_json::Value = serde_json::from_str(&json_str)?;
 
     // Parse Software Info
     let software_info = value["SPSoftwareDataType"].get(0);
@@ -167,7 +169,7 @@ fn get_mac_system_info() -> Result<DetailedSystemInfo, String> {
 }
 
 #[cfg(target_os = "linux")]
-fn get_linux_system_info() -> Result<DetailedSystemInfo, String> {
+fn get_linux_system_info() -> DashResult<DetailedSystemInfo> {
     use std::fs;
 
     // Helper to read file content trimmed
@@ -290,7 +292,8 @@ fn get_linux_system_info() -> Result<DetailedSystemInfo, String> {
 }
 
 #[cfg(target_os = "windows")]
-fn get_windows_system_info() -> Result<DetailedSystemInfo, String> {
+fn get_windows_system_info() -> DashResult<DetailedSystemInfo> {
+    use crate::error::DashError;
     use std::os::windows::process::CommandExt;
     const CREATE_NO_WINDOW: u32 = 0x08000000;
 
@@ -301,13 +304,13 @@ fn get_windows_system_info() -> Result<DetailedSystemInfo, String> {
         .args(["-NoProfile", "-Command", ps_script])
         .creation_flags(CREATE_NO_WINDOW)
         .output()
-        .map_err(|e| format!("Failed to execute PowerShell command: {}", e))?;
+        .map_err(|e| DashError::CommandFailed(format!("Failed to execute PowerShell command: {}", e)))?;
 
     if !output.status.success() {
-        return Err(format!(
+        return Err(DashError::CommandFailed(format!(
             "PowerShell command failed: {}",
             String::from_utf8_lossy(&output.stderr)
-        ));
+        )));
     }
 
     let json_str = String::from_utf8_lossy(&output.stdout);
@@ -333,8 +336,7 @@ fn get_windows_system_info() -> Result<DetailedSystemInfo, String> {
     // However, OsBuildNumber might be string in some versions? Let's use serde_json::Value to be safe or String.
     // Actually, let's try strict types but handle failure.
 
-    let info: WindowsComputerInfo = serde_json::from_str(&json_str)
-        .map_err(|e| format!("Failed to parse JSON: {} | Input: {}", e, json_str))?;
+    let info: WindowsComputerInfo = serde_json::from_str(&json_str)?;
 
     // 2. Get Hotfixes (Limit to recent 10 or just count? User said 'Hotfix(s): ...' which lists them)
     // Detailed list might be long. Let's just get IDs.
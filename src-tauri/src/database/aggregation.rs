@@ -0,0 +1,411 @@
+use rusqlite::{Connection, Result as SqliteResult};
+use std::collections::BTreeMap;
+
+// 累计计数器（网卡字节数、PSI 停顿时间）向窗口前回溯的范围，用来找到窗口前的最后一个采样
+const COUNTER_LOOKBACK_SECS: i64 = 3600;
+const U32_RANGE: u64 = 1 << 32;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Tier {
+    Hourly,
+    Daily,
+}
+
+impl Tier {
+    pub fn table(self) -> &'static str {
+        match self {
+            Tier::Hourly => "hourly_stats",
+            Tier::Daily => "daily_stats",
+        }
+    }
+
+    pub fn network_table(self) -> &'static str {
+        match self {
+            Tier::Hourly => "hourly_network_stats",
+            Tier::Daily => "daily_network_stats",
+        }
+    }
+
+    pub fn pressure_table(self) -> &'static str {
+        match self {
+            Tier::Hourly => "hourly_pressure_stats",
+            Tier::Daily => "daily_pressure_stats",
+        }
+    }
+
+    pub fn column(self) -> &'static str {
+        match self {
+            Tier::Hourly => "hour_timestamp",
+            Tier::Daily => "day_timestamp",
+        }
+    }
+}
+
+pub struct WindowStats {
+    pub avg_cpu: f64,
+    pub min_cpu: f64,
+    pub max_cpu: f64,
+    pub avg_memory: f64,
+    pub min_memory: f64,
+    pub max_memory: f64,
+    pub total_rx: u64,
+    pub total_tx: u64,
+    pub interfaces: BTreeMap<String, (u64, u64)>,
+    pub pressure: BTreeMap<String, PressureWindow>,
+}
+
+// 某个资源在窗口内的 PSI：avg10 的平均值和峰值，以及按累计值算出的停顿总时长
+#[derive(Debug, Clone, Default)]
+pub struct PressureWindow {
+    pub avg_some: f64,
+    pub max_some: f64,
+    pub avg_full: Option<f64>,
+    pub max_full: Option<f64>,
+    pub some_stall_usec: u64,
+    pub full_stall_usec: u64,
+}
+
+// 汇总 [start, end) 内的原始数据，该时段无数据时返回 None
+pub fn aggregate_window(conn: &Connection, start: i64, end: i64) -> SqliteResult<Option<WindowStats>> {
+    let (cpu_samples, avg_cpu, min_cpu, max_cpu): (i64, Option<f64>, Option<f64>, Option<f64>) = conn.query_row(
+        "SELECT COUNT(*), AVG(usage), MIN(usage), MAX(usage) FROM cpu_history WHERE timestamp >= ?1 AND timestamp < ?2",
+        [start, end],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    )?;
+    let (memory_samples, avg_memory, min_memory, max_memory): (i64, Option<f64>, Option<f64>, Option<f64>) =
+        conn.query_row(
+            "SELECT COUNT(*), AVG(usage_percent), MIN(usage_percent), MAX(usage_percent) FROM memory_history WHERE timestamp >= ?1 AND timestamp < ?2",
+            [start, end],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+    if cpu_samples == 0 && memory_samples == 0 {
+        return Ok(None);
+    }
+
+    let interfaces = network_deltas(conn, start, end)?;
+    let total_rx = interfaces.values().map(|(rx, _)| rx).sum();
+    let total_tx = interfaces.values().map(|(_, tx)| tx).sum();
+    let pressure = pressure_window(conn, start, end)?;
+
+    Ok(Some(WindowStats {
+        avg_cpu: avg_cpu.unwrap_or(0.0),
+        min_cpu: min_cpu.unwrap_or(0.0),
+        max_cpu: max_cpu.unwrap_or(0.0),
+        avg_memory: avg_memory.unwrap_or(0.0),
+        min_memory: min_memory.unwrap_or(0.0),
+        max_memory: max_memory.unwrap_or(0.0),
+        total_rx,
+        total_tx,
+        interfaces,
+        pressure,
+    }))
+}
+
+pub fn pressure_window(conn: &Connection, start: i64, end: i64) -> SqliteResult<BTreeMap<String, PressureWindow>> {
+    let mut stmt = conn.prepare(
+        "SELECT resource, AVG(some_avg10), MAX(some_avg10), AVG(full_avg10), MAX(full_avg10)
+         FROM pressure_history WHERE timestamp >= ?1 AND timestamp < ?2
+         GROUP BY resource",
+    )?;
+    let rows = stmt.query_map([start, end], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            PressureWindow {
+                avg_some: row.get::<_, Option<f64>>(1)?.unwrap_or(0.0),
+                max_some: row.get::<_, Option<f64>>(2)?.unwrap_or(0.0),
+                avg_full: row.get(3)?,
+                max_full: row.get(4)?,
+                ..Default::default()
+            },
+        ))
+    })?;
+    let mut windows: BTreeMap<String, PressureWindow> = rows.collect::<SqliteResult<_>>()?;
+
+    // total 是开机以来的 64 位累计值，只会在重启后归零，不存在回绕
+    let mut stmt = conn.prepare(
+        "SELECT resource, timestamp, some_total_usec, full_total_usec FROM pressure_history
+         WHERE timestamp >= ?1 AND timestamp < ?2
+         ORDER BY resource ASC, timestamp ASC",
+    )?;
+    let rows = stmt.query_map([start - COUNTER_LOOKBACK_SECS, end], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, Option<i64>>(3)?,
+        ))
+    })?;
+    let mut previous: Option<(String, u64, Option<u64>)> = None;
+    for row in rows {
+        let (resource, timestamp, some, full) = row?;
+        let (some, full) = (some.max(0) as u64, full.map(|full| full.max(0) as u64));
+        if let (Some((prev_resource, prev_some, prev_full)), Some(window)) = (&previous, windows.get_mut(&resource)) {
+            if *prev_resource == resource && timestamp >= start {
+                window.some_stall_usec += reset_delta(*prev_some, some);
+                if let (Some(prev_full), Some(full)) = (prev_full, full) {
+                    window.full_stall_usec += reset_delta(*prev_full, full);
+                }
+            }
+        }
+        previous = Some((resource, some, full));
+    }
+    Ok(windows)
+}
+
+// 网卡计数器是累计值，按网卡逐对相邻采样计算增量，窗口前的最后一个采样作为起点
+pub fn network_deltas(conn: &Connection, start: i64, end: i64) -> SqliteResult<BTreeMap<String, (u64, u64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT interface_name, timestamp, rx_bytes, tx_bytes FROM network_history
+         WHERE timestamp >= ?1 AND timestamp < ?2
+         ORDER BY interface_name ASC, timestamp ASC",
+    )?;
+    let rows = stmt.query_map([start - COUNTER_LOOKBACK_SECS, end], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, i64>(3)?,
+        ))
+    })?;
+
+    let mut totals: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+    let mut previous: Option<(String, u64, u64)> = None;
+    for row in rows {
+        let (name, timestamp, rx, tx) = row?;
+        let (rx, tx) = (rx.max(0) as u64, tx.max(0) as u64);
+        if let Some((prev_name, prev_rx, prev_tx)) = &previous {
+            if *prev_name == name && timestamp >= start {
+                let entry = totals.entry(name.clone()).or_default();
+                entry.0 += counter_delta(*prev_rx, rx);
+                entry.1 += counter_delta(*prev_tx, tx);
+            }
+        }
+        if timestamp >= start {
+            totals.entry(name.clone()).or_default();
+        }
+        previous = Some((name, rx, tx));
+    }
+    Ok(totals)
+}
+
+// 计数器变小有两种可能：32 位计数器回绕（旧值接近上限），或网卡重置/系统重启后从 0 重新计数
+pub fn counter_delta(previous: u64, current: u64) -> u64 {
+    if current < previous && (U32_RANGE / 2..U32_RANGE).contains(&previous) {
+        let wrapped = U32_RANGE - previous + current;
+        if wrapped < U32_RANGE / 2 {
+            return wrapped;
+        }
+    }
+    reset_delta(previous, current)
+}
+
+// 计数器变小说明已经重置（重启或设备重建），重置后的值就是这段时间的增量
+fn reset_delta(previous: u64, current: u64) -> u64 {
+    current.checked_sub(previous).unwrap_or(current)
+}
+
+pub fn upsert_stats(conn: &Connection, tier: Tier, timestamp: i64, stats: &WindowStats) -> SqliteResult<()> {
+    conn.execute(
+        &format!(
+            "INSERT INTO {table} ({column}, avg_cpu_usage, max_cpu_usage, avg_memory_usage, max_memory_usage, total_rx_bytes, total_tx_bytes,
+                min_cpu_usage, min_memory_usage)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+             ON CONFLICT({column}) DO UPDATE SET
+                avg_cpu_usage = excluded.avg_cpu_usage,
+                max_cpu_usage = excluded.max_cpu_usage,
+                avg_memory_usage = excluded.avg_memory_usage,
+                max_memory_usage = excluded.max_memory_usage,
+                min_cpu_usage = excluded.min_cpu_usage,
+                min_memory_usage = excluded.min_memory_usage,
+                total_rx_bytes = excluded.total_rx_bytes,
+                total_tx_bytes = excluded.total_tx_bytes",
+            table = tier.table(),
+            column = tier.column(),
+        ),
+        rusqlite::params![
+            timestamp,
+            stats.avg_cpu,
+            stats.max_cpu,
+            stats.avg_memory,
+            stats.max_memory,
+            stats.total_rx as i64,
+            stats.total_tx as i64,
+            stats.min_cpu,
+            stats.min_memory,
+        ],
+    )?;
+
+    conn.execute(
+        &format!(
+            "DELETE FROM {table} WHERE {column} = ?1",
+            table = tier.network_table(),
+            column = tier.column(),
+        ),
+        [timestamp],
+    )?;
+    let mut stmt = conn.prepare(&format!(
+        "INSERT INTO {table} ({column}, interface_name, rx_bytes, tx_bytes) VALUES (?1, ?2, ?3, ?4)",
+        table = tier.network_table(),
+        column = tier.column(),
+    ))?;
+    for (name, (rx, tx)) in &stats.interfaces {
+        stmt.execute(rusqlite::params![timestamp, name, *rx as i64, *tx as i64])?;
+    }
+
+    conn.execute(
+        &format!(
+            "DELETE FROM {table} WHERE {column} = ?1",
+            table = tier.pressure_table(),
+            column = tier.column(),
+        ),
+        [timestamp],
+    )?;
+    let mut stmt = conn.prepare(&format!(
+        "INSERT INTO {table} ({column}, resource, avg_some, max_some, avg_full, max_full, some_stall_usec, full_stall_usec)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        table = tier.pressure_table(),
+        column = tier.column(),
+    ))?;
+    for (resource, window) in &stats.pressure {
+        stmt.execute(rusqlite::params![
+            timestamp,
+            resource,
+            window.avg_some,
+            window.max_some,
+            window.avg_full,
+            window.max_full,
+            window.some_stall_usec as i64,
+            window.full_stall_usec as i64,
+        ])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::schema::{SCHEMA, V9_PRESSURE};
+
+    fn memory_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        conn.execute_batch(V9_PRESSURE).unwrap();
+        conn
+    }
+
+    fn insert_network(conn: &Connection, timestamp: i64, name: &str, rx: u64, tx: u64) {
+        conn.execute(
+            "INSERT INTO network_history (timestamp, interface_name, rx_bytes, tx_bytes, rx_speed, tx_speed)
+             VALUES (?1, ?2, ?3, ?4, 0, 0)",
+            rusqlite::params![timestamp, name, rx as i64, tx as i64],
+        )
+        .unwrap();
+    }
+
+    fn insert_pressure(conn: &Connection, timestamp: i64, resource: &str, some: u64, full: Option<u64>) {
+        conn.execute(
+            "INSERT INTO pressure_history (timestamp, resource, some_avg10, some_avg60, some_avg300, some_total_usec,
+                full_avg10, full_avg60, full_avg300, full_total_usec)
+             VALUES (?1, ?2, 1.0, 0, 0, ?3, ?4, 0, 0, ?5)",
+            rusqlite::params![timestamp, resource, some as i64, full.map(|_| 0.5), full.map(|full| full as i64)],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn counter_delta_monotonic() {
+        assert_eq!(counter_delta(100, 250), 150);
+        assert_eq!(counter_delta(7, 7), 0);
+    }
+
+    #[test]
+    fn counter_delta_u32_wrap() {
+        assert_eq!(counter_delta(U32_RANGE - 100, 50), 150);
+    }
+
+    #[test]
+    fn counter_delta_reset_to_zero() {
+        // 旧值不在 32 位上限附近，视为重置
+        assert_eq!(counter_delta(5_000, 0), 0);
+        assert_eq!(counter_delta(5_000, 300), 300);
+        // 64 位计数器超过 u32 范围后变小，也只能是重置
+        assert_eq!(counter_delta(U32_RANGE * 4, 1_000), 1_000);
+        // 旧值接近上限但回绕后的增量不合理（超过半个范围），按重置处理
+        assert_eq!(counter_delta(U32_RANGE / 2, U32_RANGE / 2 - 1), U32_RANGE / 2 - 1);
+    }
+
+    #[test]
+    fn network_deltas_use_sample_before_window() {
+        let conn = memory_db();
+        let start = 10_000;
+        // 窗口前的最后一个采样作为起点，更早的采样不影响结果
+        insert_network(&conn, start - 120, "eth0", 500, 50);
+        insert_network(&conn, start - 60, "eth0", 1_000, 100);
+        insert_network(&conn, start + 60, "eth0", 1_600, 160);
+        insert_network(&conn, start + 120, "eth0", 2_000, 200);
+        // 超出窗口的采样不计入
+        insert_network(&conn, start + 3_600, "eth0", 9_000, 900);
+
+        let totals = network_deltas(&conn, start, start + 3_600).unwrap();
+        assert_eq!(totals.get("eth0"), Some(&(1_000, 100)));
+    }
+
+    #[test]
+    fn network_deltas_interface_appearing_mid_window() {
+        let conn = memory_db();
+        let start = 10_000;
+        insert_network(&conn, start - 60, "eth0", 1_000, 100);
+        insert_network(&conn, start + 60, "eth0", 1_500, 150);
+        // wlan0 在窗口中途才出现，第一个采样没有起点，只统计之后的增量
+        insert_network(&conn, start + 600, "wlan0", 40_000, 4_000);
+        insert_network(&conn, start + 660, "wlan0", 41_000, 4_300);
+        // 只在窗口前出现过的网卡不出现在结果中
+        insert_network(&conn, start - 30, "docker0", 10, 10);
+
+        let totals = network_deltas(&conn, start, start + 3_600).unwrap();
+        assert_eq!(totals.get("eth0"), Some(&(500, 50)));
+        assert_eq!(totals.get("wlan0"), Some(&(1_000, 300)));
+        assert!(!totals.contains_key("docker0"));
+    }
+
+    #[test]
+    fn network_deltas_single_sample_interface_is_zero() {
+        let conn = memory_db();
+        insert_network(&conn, 10_100, "eth1", 123_456, 789);
+        let totals = network_deltas(&conn, 10_000, 13_600).unwrap();
+        assert_eq!(totals.get("eth1"), Some(&(0, 0)));
+    }
+
+    #[test]
+    fn pressure_window_stall_per_resource() {
+        let conn = memory_db();
+        let start = 10_000;
+        // 窗口前的采样作为起点
+        insert_pressure(&conn, start - 30, "cpu", 1_000, None);
+        insert_pressure(&conn, start + 30, "cpu", 1_500, None);
+        insert_pressure(&conn, start + 90, "cpu", 2_500, None);
+        insert_pressure(&conn, start - 30, "memory", 100, Some(40));
+        insert_pressure(&conn, start + 30, "memory", 400, Some(90));
+        // 重启后累计值归零，重置后的值计入增量
+        insert_pressure(&conn, start + 90, "memory", 50, Some(10));
+        // io 只有窗口内的一个采样，没有起点
+        insert_pressure(&conn, start + 60, "io", 7_000, Some(3_000));
+
+        let windows = pressure_window(&conn, start, start + 3_600).unwrap();
+        assert_eq!(windows.len(), 3);
+
+        let cpu = &windows["cpu"];
+        assert_eq!(cpu.some_stall_usec, 1_500);
+        assert_eq!(cpu.full_stall_usec, 0);
+        assert_eq!(cpu.avg_full, None);
+
+        let memory = &windows["memory"];
+        assert_eq!(memory.some_stall_usec, 300 + 50);
+        assert_eq!(memory.full_stall_usec, 50 + 10);
+        assert_eq!(memory.avg_full, Some(0.5));
+
+        let io = &windows["io"];
+        assert_eq!(io.some_stall_usec, 0);
+        assert_eq!(io.full_stall_usec, 0);
+    }
+}
@@ -0,0 +1,243 @@
+use crate::database::schema::{
+    SCHEMA, V2_SETTINGS_AND_DAILY_UNIQUE, V3_HOURLY_UNIQUE_AND_WATERMARKS, V4_INTERFACE_TRAFFIC,
+    V5_PROCESS_ACTIONS, V6_PROCESS_HISTORY, V7_WATCH_TRIGGERS, V8_CGROUP_HISTORY, V9_PRESSURE,
+    V10_CPU_TIMES, V11_LOAD_AVERAGE, V12_MEMORY_BREAKDOWN, V13_MIN_USAGE,
+};
+use rusqlite::{Connection, Result as SqliteResult, Transaction};
+use std::path::Path;
+
+// 保留的迁移前备份数量
+const MAX_BACKUPS: usize = 5;
+
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub up: fn(&Transaction) -> SqliteResult<()>,
+}
+
+// 按版本号升序排列，已发布的迁移不可修改，只能追加
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "initial schema",
+        up: |tx| tx.execute_batch(SCHEMA),
+    },
+    Migration {
+        version: 2,
+        description: "settings table and unique daily_stats days",
+        up: |tx| tx.execute_batch(V2_SETTINGS_AND_DAILY_UNIQUE),
+    },
+    Migration {
+        version: 3,
+        description: "aligned hourly_stats and aggregation watermarks",
+        up: |tx| tx.execute_batch(V3_HOURLY_UNIQUE_AND_WATERMARKS),
+    },
+    Migration {
+        version: 4,
+        description: "per-interface hourly and daily traffic",
+        up: |tx| tx.execute_batch(V4_INTERFACE_TRAFFIC),
+    },
+    Migration {
+        version: 5,
+        description: "process action audit log",
+        up: |tx| tx.execute_batch(V5_PROCESS_ACTIONS),
+    },
+    Migration {
+        version: 6,
+        description: "top process history",
+        up: |tx| tx.execute_batch(V6_PROCESS_HISTORY),
+    },
+    Migration {
+        version: 7,
+        description: "process watch rule triggers",
+        up: |tx| tx.execute_batch(V7_WATCH_TRIGGERS),
+    },
+    Migration {
+        version: 8,
+        description: "cgroup resource history",
+        up: |tx| tx.execute_batch(V8_CGROUP_HISTORY),
+    },
+    Migration {
+        version: 9,
+        description: "pressure stall history and aggregates",
+        up: |tx| tx.execute_batch(V9_PRESSURE),
+    },
+    Migration {
+        version: 10,
+        description: "cpu time breakdown and per-core history",
+        up: |tx| tx.execute_batch(V10_CPU_TIMES),
+    },
+    Migration {
+        version: 11,
+        description: "load averages in cpu_history",
+        up: |tx| tx.execute_batch(V11_LOAD_AVERAGE),
+    },
+    Migration {
+        version: 12,
+        description: "detailed memory breakdown in memory_history",
+        up: |tx| tx.execute_batch(V12_MEMORY_BREAKDOWN),
+    },
+    Migration {
+        version: 13,
+        description: "minimum cpu and memory usage in hourly and daily stats",
+        up: |tx| tx.execute_batch(V13_MIN_USAGE),
+    },
+];
+
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+pub fn current_version(conn: &Connection) -> SqliteResult<u32> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+pub fn migrate(conn: &mut Connection, backup_dir: &Path) -> SqliteResult<()> {
+    let current = current_version(conn)?;
+    let latest = latest_version();
+
+    if current > latest {
+        eprintln!(
+            "database schema version {} is newer than supported version {}, skipping migrations",
+            current, latest
+        );
+        return Ok(());
+    }
+    if current == latest {
+        return Ok(());
+    }
+
+    if has_user_tables(conn)? {
+        backup(conn, backup_dir, current)?;
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let tx = conn.transaction()?;
+        (migration.up)(&tx)?;
+        tx.pragma_update(None, "user_version", migration.version)?;
+        tx.commit()?;
+        eprintln!(
+            "database migrated to version {} ({})",
+            migration.version, migration.description
+        );
+    }
+
+    Ok(())
+}
+
+fn has_user_tables(conn: &Connection) -> SqliteResult<bool> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+// 使用 VACUUM INTO 生成一致的数据库副本，避免直接复制文件时读到写了一半的页
+fn backup(conn: &Connection, backup_dir: &Path, version: u32) -> SqliteResult<()> {
+    std::fs::create_dir_all(backup_dir).ok();
+    let file_name = format!(
+        "data-v{}-{}.db",
+        version,
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
+    );
+    let path = backup_dir.join(file_name);
+    conn.execute("VACUUM INTO ?1", [path.to_string_lossy()])?;
+    prune_backups(backup_dir);
+    Ok(())
+}
+
+fn prune_backups(backup_dir: &Path) {
+    let mut backups: Vec<_> = match std::fs::read_dir(backup_dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|n| n.starts_with("data-v") && n.ends_with(".db"))
+                    .unwrap_or(false)
+            })
+            .collect(),
+        Err(_) => return,
+    };
+    if backups.len() <= MAX_BACKUPS {
+        return;
+    }
+    backups.sort_by_key(|p| p.metadata().and_then(|m| m.modified()).ok());
+    let excess = backups.len() - MAX_BACKUPS;
+    for path in backups.into_iter().take(excess) {
+        std::fs::remove_file(path).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dashsys-migrations-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn backups_in(dir: &Path) -> Vec<PathBuf> {
+        std::fs::read_dir(dir)
+            .map(|entries| entries.flatten().map(|e| e.path()).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn migrates_legacy_database_with_backup() {
+        let dir = temp_dir("legacy");
+        let backup_dir = dir.join("backups");
+        let mut conn = Connection::open(dir.join("data.db")).unwrap();
+        // 引入迁移之前的数据库：已有表和数据，但 user_version 仍为 0
+        conn.execute_batch(SCHEMA).unwrap();
+        conn.execute(
+            "INSERT INTO cpu_history (timestamp, usage, frequency) VALUES (1000, 12.5, 2400)",
+            [],
+        )
+        .unwrap();
+        assert_eq!(current_version(&conn).unwrap(), 0);
+
+        migrate(&mut conn, &backup_dir).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+        let rows: i64 = conn.query_row("SELECT COUNT(*) FROM cpu_history", [], |row| row.get(0)).unwrap();
+        assert_eq!(rows, 1);
+
+        let backups = backups_in(&backup_dir);
+        assert_eq!(backups.len(), 1);
+        let name = backups[0].file_name().unwrap().to_string_lossy().into_owned();
+        assert!(name.starts_with("data-v0-") && name.ends_with(".db"), "{}", name);
+        let backup = Connection::open(&backups[0]).unwrap();
+        assert_eq!(current_version(&backup).unwrap(), 0);
+        let rows: i64 = backup.query_row("SELECT COUNT(*) FROM cpu_history", [], |row| row.get(0)).unwrap();
+        assert_eq!(rows, 1);
+
+        // 已是最新版本时不再迁移，也不再备份
+        migrate(&mut conn, &backup_dir).unwrap();
+        assert_eq!(backups_in(&backup_dir).len(), 1);
+
+        drop(backup);
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn fresh_database_skips_backup() {
+        let dir = temp_dir("fresh");
+        let backup_dir = dir.join("backups");
+        let mut conn = Connection::open(dir.join("data.db")).unwrap();
+
+        migrate(&mut conn, &backup_dir).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+        assert!(backups_in(&backup_dir).is_empty());
+
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,4 +1,9 @@
+pub mod aggregation;
+pub mod migrations;
+pub mod query;
+pub mod retention;
 pub mod schema;
 pub mod service;
+pub mod timezone;
 
 pub use service::DatabaseService;
\ No newline at end of file
@@ -0,0 +1,214 @@
+use crate::database::retention::RetentionPolicy;
+use crate::error::{DashError, DashResult};
+use rusqlite::{Connection, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_POINTS: u32 = 500;
+const MAX_POINTS: u32 = 5000;
+const HOUR_SECS: i64 = 3600;
+const DAY_SECS: i64 = 86400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryMetric {
+    Cpu,
+    Memory,
+    NetworkRx,
+    NetworkTx,
+    DiskRead,
+    DiskWrite,
+    // 1 分钟负载均值
+    LoadAverage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryTier {
+    Raw,
+    Hourly,
+    Daily,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryQuery {
+    pub metric: HistoryMetric,
+    pub start: i64,
+    pub end: i64,
+    pub points: Option<u32>,
+    pub bucket_secs: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryBucket {
+    pub timestamp: i64,
+    // 汇总层中没有真实最小值的桶为 None，不用平均值冒充
+    pub min: Option<f64>,
+    pub avg: f64,
+    pub max: f64,
+    pub samples: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryQueryResponse {
+    pub metric: HistoryMetric,
+    pub tier: HistoryTier,
+    pub bucket_secs: i64,
+    pub buckets: Vec<HistoryBucket>,
+}
+
+pub struct ResolvedQuery {
+    pub metric: HistoryMetric,
+    pub tier: HistoryTier,
+    pub start: i64,
+    pub end: i64,
+    pub bucket_secs: i64,
+}
+
+impl HistoryMetric {
+    // 磁盘和负载只有原始数据，没有汇总层
+    fn has_aggregates(self) -> bool {
+        !matches!(
+            self,
+            HistoryMetric::DiskRead | HistoryMetric::DiskWrite | HistoryMetric::LoadAverage
+        )
+    }
+
+    fn retention_name(self) -> &'static str {
+        match self {
+            HistoryMetric::Cpu | HistoryMetric::LoadAverage => "cpu",
+            HistoryMetric::Memory => "memory",
+            HistoryMetric::NetworkRx | HistoryMetric::NetworkTx => "network",
+            HistoryMetric::DiskRead | HistoryMetric::DiskWrite => "disk",
+        }
+    }
+}
+
+impl HistoryQuery {
+    // 桶宽度决定分辨率：能用汇总层满足的就不读原始数据，且不读取已超出保留期的层
+    pub fn resolve(&self, policy: &RetentionPolicy, now: i64) -> DashResult<ResolvedQuery> {
+        if self.end <= self.start {
+            return Err(DashError::InvalidArgument("end must be greater than start".to_string()));
+        }
+        let range = self.end - self.start;
+        let bucket_secs = match (self.bucket_secs, self.points) {
+            (Some(width), _) if width > 0 => width,
+            (Some(_), _) => return Err(DashError::InvalidArgument("bucket_secs must be positive".to_string())),
+            (None, Some(0)) => return Err(DashError::InvalidArgument("points must be positive".to_string())),
+            (None, points) => {
+                let points = points.unwrap_or(DEFAULT_POINTS).min(MAX_POINTS) as i64;
+                ((range + points - 1) / points).max(1)
+            }
+        };
+        if range / bucket_secs > MAX_POINTS as i64 {
+            return Err(DashError::InvalidArgument(format!(
+                "query would return more than {} buckets",
+                MAX_POINTS
+            )));
+        }
+
+        let tier = if !self.metric.has_aggregates() {
+            HistoryTier::Raw
+        } else {
+            let raw_from = now - policy.raw_days_for(self.metric.retention_name()) as i64 * DAY_SECS;
+            let hourly_from = now - policy.hourly_days as i64 * DAY_SECS;
+            let mut tier = if bucket_secs >= DAY_SECS {
+                HistoryTier::Daily
+            } else if bucket_secs >= HOUR_SECS {
+                HistoryTier::Hourly
+            } else {
+                HistoryTier::Raw
+            };
+            if tier == HistoryTier::Raw && self.start < raw_from {
+                tier = HistoryTier::Hourly;
+            }
+            if tier == HistoryTier::Hourly && self.start < hourly_from {
+                tier = HistoryTier::Daily;
+            }
+            tier
+        };
+
+        Ok(ResolvedQuery {
+            metric: self.metric,
+            tier,
+            start: self.start,
+            end: self.end,
+            bucket_secs,
+        })
+    }
+}
+
+// 每个数据源给出 (ts, lo, mid, hi) 四列：原始数据三列相同，汇总层用 min/avg/max。
+// 网络汇总只有总量、迁移前的汇总行没有最小值，lo 为 NULL
+fn source_sql(metric: HistoryMetric, tier: HistoryTier) -> &'static str {
+    match (tier, metric) {
+        (HistoryTier::Raw, HistoryMetric::Cpu) => {
+            "SELECT timestamp AS ts, usage AS lo, usage AS mid, usage AS hi FROM cpu_history"
+        }
+        (HistoryTier::Raw, HistoryMetric::LoadAverage) => {
+            "SELECT timestamp AS ts, load_avg_1 AS lo, load_avg_1 AS mid, load_avg_1 AS hi FROM cpu_history WHERE load_avg_1 IS NOT NULL"
+        }
+        (HistoryTier::Raw, HistoryMetric::Memory) => {
+            "SELECT timestamp AS ts, usage_percent AS lo, usage_percent AS mid, usage_percent AS hi FROM memory_history"
+        }
+        (HistoryTier::Raw, HistoryMetric::NetworkRx) => {
+            "SELECT timestamp AS ts, SUM(rx_speed) AS lo, SUM(rx_speed) AS mid, SUM(rx_speed) AS hi FROM network_history WHERE timestamp >= ?1 AND timestamp < ?2 GROUP BY timestamp"
+        }
+        (HistoryTier::Raw, HistoryMetric::NetworkTx) => {
+            "SELECT timestamp AS ts, SUM(tx_speed) AS lo, SUM(tx_speed) AS mid, SUM(tx_speed) AS hi FROM network_history WHERE timestamp >= ?1 AND timestamp < ?2 GROUP BY timestamp"
+        }
+        (HistoryTier::Raw, HistoryMetric::DiskRead) => {
+            "SELECT timestamp AS ts, SUM(read_speed) AS lo, SUM(read_speed) AS mid, SUM(read_speed) AS hi FROM disk_history WHERE timestamp >= ?1 AND timestamp < ?2 GROUP BY timestamp"
+        }
+        (HistoryTier::Raw, HistoryMetric::DiskWrite) => {
+            "SELECT timestamp AS ts, SUM(write_speed) AS lo, SUM(write_speed) AS mid, SUM(write_speed) AS hi FROM disk_history WHERE timestamp >= ?1 AND timestamp < ?2 GROUP BY timestamp"
+        }
+        (HistoryTier::Hourly, HistoryMetric::Cpu) => {
+            "SELECT hour_timestamp AS ts, min_cpu_usage AS lo, avg_cpu_usage AS mid, max_cpu_usage AS hi FROM hourly_stats"
+        }
+        (HistoryTier::Hourly, HistoryMetric::Memory) => {
+            "SELECT hour_timestamp AS ts, min_memory_usage AS lo, avg_memory_usage AS mid, max_memory_usage AS hi FROM hourly_stats"
+        }
+        (HistoryTier::Hourly, HistoryMetric::NetworkRx) => {
+            "SELECT hour_timestamp AS ts, NULL AS lo, total_rx_bytes / 3600.0 AS mid, total_rx_bytes / 3600.0 AS hi FROM hourly_stats"
+        }
+        (HistoryTier::Hourly, HistoryMetric::NetworkTx) => {
+            "SELECT hour_timestamp AS ts, NULL AS lo, total_tx_bytes / 3600.0 AS mid, total_tx_bytes / 3600.0 AS hi FROM hourly_stats"
+        }
+        (HistoryTier::Daily, HistoryMetric::Cpu) => {
+            "SELECT day_timestamp AS ts, min_cpu_usage AS lo, avg_cpu_usage AS mid, max_cpu_usage AS hi FROM daily_stats"
+        }
+        (HistoryTier::Daily, HistoryMetric::Memory) => {
+            "SELECT day_timestamp AS ts, min_memory_usage AS lo, avg_memory_usage AS mid, max_memory_usage AS hi FROM daily_stats"
+        }
+        (HistoryTier::Daily, HistoryMetric::NetworkRx) => {
+            "SELECT day_timestamp AS ts, NULL AS lo, total_rx_bytes / 86400.0 AS mid, total_rx_bytes / 86400.0 AS hi FROM daily_stats"
+        }
+        (HistoryTier::Daily, HistoryMetric::NetworkTx) => {
+            "SELECT day_timestamp AS ts, NULL AS lo, total_tx_bytes / 86400.0 AS mid, total_tx_bytes / 86400.0 AS hi FROM daily_stats"
+        }
+        (_, HistoryMetric::DiskRead) | (_, HistoryMetric::DiskWrite) | (_, HistoryMetric::LoadAverage) => {
+            unreachable!("disk and load metrics are only stored as raw samples")
+        }
+    }
+}
+
+pub fn query(conn: &Connection, query: &ResolvedQuery) -> SqliteResult<Vec<HistoryBucket>> {
+    let sql = format!(
+        "SELECT (ts - ?1) / ?3 AS bucket, MIN(lo), AVG(mid), MAX(hi), COUNT(*)
+         FROM ({}) WHERE ts >= ?1 AND ts < ?2
+         GROUP BY bucket ORDER BY bucket ASC",
+        source_sql(query.metric, query.tier)
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let buckets = stmt.query_map([query.start, query.end, query.bucket_secs], |row| {
+        let bucket: i64 = row.get(0)?;
+        Ok(HistoryBucket {
+            timestamp: query.start + bucket * query.bucket_secs,
+            min: row.get(1)?,
+            avg: row.get::<_, Option<f64>>(2)?.unwrap_or(0.0),
+            max: row.get::<_, Option<f64>>(3)?.unwrap_or(0.0),
+            samples: row.get(4)?,
+        })
+    })?;
+    buckets.collect()
+}
@@ -0,0 +1,194 @@
+use crate::error::{DashError, DashResult};
+use chrono::{Duration, Utc};
+use rusqlite::{Connection, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const SETTING_RETENTION_POLICY: &str = "retention_policy";
+
+// 指标名与原始数据表的对应关系，per-metric 覆盖按这里的名字配置
+pub const RAW_TABLES: &[(&str, &str)] = &[
+    ("cpu", "cpu_history"),
+    ("cpu_core", "cpu_core_history"),
+    ("memory", "memory_history"),
+    ("network", "network_history"),
+    ("disk", "disk_history"),
+    ("process", "process_history"),
+    ("cgroup", "cgroup_history"),
+    ("pressure", "pressure_history"),
+];
+
+// 操作审计与监视规则触发记录单独按 audit_days 清理，不受 max_database_mb 裁剪影响
+pub const AUDIT_TABLES: &[&str] = &["process_actions", "watch_triggers"];
+
+// 超出容量上限时至少保留最近这么多的原始数据
+const MIN_RAW_KEEP_SECS: i64 = 24 * 3600;
+const MAX_TRIM_ROUNDS: usize = 400;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RetentionPolicy {
+    pub raw_days: u32,
+    pub hourly_days: u32,
+    pub daily_days: u32,
+    #[serde(default)]
+    pub metric_overrides: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub max_database_mb: Option<u64>,
+    #[serde(default = "default_audit_days")]
+    pub audit_days: u32,
+}
+
+fn default_audit_days() -> u32 {
+    365
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            raw_days: 365,
+            hourly_days: 30,
+            daily_days: 365,
+            metric_overrides: BTreeMap::new(),
+            max_database_mb: None,
+            audit_days: default_audit_days(),
+        }
+    }
+}
+
+impl RetentionPolicy {
+    pub fn validate(&self) -> DashResult<()> {
+        if self.raw_days == 0 || self.hourly_days == 0 || self.daily_days == 0 || self.audit_days == 0 {
+            return Err(DashError::InvalidArgument("retention days must be at least 1".to_string()));
+        }
+        for (metric, days) in &self.metric_overrides {
+            if !RAW_TABLES.iter().any(|(name, _)| name == metric) {
+                return Err(DashError::InvalidArgument(format!(
+                    "unknown metric in retention overrides: {}",
+                    metric
+                )));
+            }
+            if *days == 0 {
+                return Err(DashError::InvalidArgument(format!(
+                    "retention days for {} must be at least 1",
+                    metric
+                )));
+            }
+        }
+        if self.max_database_mb == Some(0) {
+            return Err(DashError::InvalidArgument("max_database_mb must be at least 1".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn raw_days_for(&self, metric: &str) -> u32 {
+        self.metric_overrides
+            .get(metric)
+            .copied()
+            .unwrap_or(self.raw_days)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CleanupReport {
+    pub deleted_rows: usize,
+    pub database_bytes: u64,
+}
+
+pub fn enforce(conn: &Connection, policy: &RetentionPolicy) -> SqliteResult<usize> {
+    let now = Utc::now();
+    let cutoff = |days: u32| (now - Duration::days(days as i64)).timestamp();
+    let mut deleted = 0;
+
+    for (metric, table) in RAW_TABLES {
+        deleted += conn.execute(
+            &format!("DELETE FROM {} WHERE timestamp < ?1", table),
+            [cutoff(policy.raw_days_for(metric))],
+        )?;
+    }
+    deleted += conn.execute(
+        "DELETE FROM hourly_stats WHERE hour_timestamp < ?1",
+        [cutoff(policy.hourly_days)],
+    )?;
+    deleted += conn.execute(
+        "DELETE FROM daily_stats WHERE day_timestamp < ?1",
+        [cutoff(policy.daily_days)],
+    )?;
+    for table in AUDIT_TABLES {
+        deleted += conn.execute(
+            &format!("DELETE FROM {} WHERE timestamp < ?1", table),
+            [cutoff(policy.audit_days)],
+        )?;
+    }
+
+    if let Some(max_mb) = policy.max_database_mb {
+        deleted += trim_to_size(conn, max_mb * 1024 * 1024)?;
+    }
+    Ok(deleted)
+}
+
+// 已使用页（不含空闲页）占用的字节数，删除后无需等 VACUUM 即可反映变化
+pub fn used_bytes(conn: &Connection) -> SqliteResult<u64> {
+    let page_count: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+    let freelist_count: i64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+    let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+    Ok(((page_count - freelist_count).max(0) * page_size) as u64)
+}
+
+pub fn file_bytes(conn: &Connection) -> SqliteResult<u64> {
+    let page_count: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+    let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+    Ok((page_count * page_size) as u64)
+}
+
+// 超出容量上限时按天删除最旧的原始数据，汇总数据体积很小、审计记录需要完整保留，都不参与裁剪
+fn trim_to_size(conn: &Connection, max_bytes: u64) -> SqliteResult<usize> {
+    let keep_from = Utc::now().timestamp() - MIN_RAW_KEEP_SECS;
+    let mut deleted = 0;
+
+    for _ in 0..MAX_TRIM_ROUNDS {
+        if used_bytes(conn)? <= max_bytes {
+            break;
+        }
+        let cutoff = match oldest_raw_timestamp(conn)? {
+            Some(ts) if ts < keep_from => (ts + 24 * 3600).min(keep_from),
+            _ => break,
+        };
+        for (_, table) in RAW_TABLES {
+            deleted += conn.execute(&format!("DELETE FROM {} WHERE timestamp < ?1", table), [cutoff])?;
+        }
+    }
+    Ok(deleted)
+}
+
+fn oldest_raw_timestamp(conn: &Connection) -> SqliteResult<Option<i64>> {
+    let union = RAW_TABLES
+        .iter()
+        .map(|(_, table)| format!("SELECT MIN(timestamp) AS ts FROM {}", table))
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
+    conn.query_row(&format!("SELECT MIN(ts) FROM ({})", union), [], |row| row.get(0))
+}
+
+// 切换为增量 auto_vacuum 需要一次完整 VACUUM，会重写整个文件；只在启动时、后台线程开始写入之前执行
+pub fn enable_incremental_vacuum(conn: &Connection) -> SqliteResult<()> {
+    if auto_vacuum_mode(conn)? != 2 {
+        conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL; VACUUM;")?;
+    }
+    Ok(())
+}
+
+// 定期任务只回收空闲页，不会重写整个文件；启动时没能切换为增量模式则跳过
+pub fn compact(conn: &Connection) -> SqliteResult<()> {
+    if auto_vacuum_mode(conn)? != 2 {
+        return Ok(());
+    }
+    // incremental_vacuum 每 step 一次只释放一页，需要一直执行到结束
+    let mut stmt = conn.prepare("PRAGMA incremental_vacuum")?;
+    let mut rows = stmt.query([])?;
+    while rows.next()?.is_some() {}
+    Ok(())
+}
+
+fn auto_vacuum_mode(conn: &Connection) -> SqliteResult<i64> {
+    conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0))
+}
@@ -71,4 +71,246 @@ CREATE INDEX IF NOT EXISTS idx_network_timestamp ON network_history(timestamp);
 CREATE INDEX IF NOT EXISTS idx_disk_timestamp ON disk_history(timestamp);
 CREATE INDEX IF NOT EXISTS idx_hourly_timestamp ON hourly_stats(hour_timestamp);
 CREATE INDEX IF NOT EXISTS idx_daily_timestamp ON daily_stats(day_timestamp);
-";
\ No newline at end of file
+";
+
+// v2: 通用设置表，daily_stats 每天仅保留一行
+pub const V2_SETTINGS_AND_DAILY_UNIQUE: &str = "
+CREATE TABLE IF NOT EXISTS settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+DELETE FROM daily_stats WHERE id NOT IN (
+    SELECT MAX(id) FROM daily_stats GROUP BY day_timestamp
+);
+DROP INDEX IF EXISTS idx_daily_timestamp;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_daily_timestamp ON daily_stats(day_timestamp);
+";
+
+// v3: 小时汇总按整点唯一，并记录各汇总任务的进度
+pub const V3_HOURLY_UNIQUE_AND_WATERMARKS: &str = "
+CREATE TABLE IF NOT EXISTS aggregation_watermarks (
+    name TEXT PRIMARY KEY,
+    last_timestamp INTEGER NOT NULL
+);
+
+-- 旧版本按滚动窗口写入的数据彼此重叠且未对齐，清空后由回填按整点重建
+DELETE FROM hourly_stats;
+DROP INDEX IF EXISTS idx_hourly_timestamp;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_hourly_timestamp ON hourly_stats(hour_timestamp);
+";
+
+// v4: 按网卡拆分的小时/日流量，随父汇总行一起删除
+pub const V4_INTERFACE_TRAFFIC: &str = "
+CREATE TABLE IF NOT EXISTS hourly_network_stats (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    hour_timestamp INTEGER NOT NULL REFERENCES hourly_stats(hour_timestamp) ON DELETE CASCADE,
+    interface_name TEXT NOT NULL,
+    rx_bytes INTEGER NOT NULL,
+    tx_bytes INTEGER NOT NULL,
+    UNIQUE(hour_timestamp, interface_name)
+);
+
+CREATE TABLE IF NOT EXISTS daily_network_stats (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    day_timestamp INTEGER NOT NULL REFERENCES daily_stats(day_timestamp) ON DELETE CASCADE,
+    interface_name TEXT NOT NULL,
+    rx_bytes INTEGER NOT NULL,
+    tx_bytes INTEGER NOT NULL,
+    UNIQUE(day_timestamp, interface_name)
+);
+
+-- 旧的流量合计直接累加了累计计数器，重置进度后由回填重新计算
+DELETE FROM aggregation_watermarks;
+";
+
+// v5: 进程管理操作的审计日志，只追加不修改
+pub const V5_PROCESS_ACTIONS: &str = "
+CREATE TABLE IF NOT EXISTS process_actions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    actor TEXT NOT NULL,
+    action TEXT NOT NULL,
+    signal TEXT,
+    pid INTEGER NOT NULL,
+    process_name TEXT,
+    command_line TEXT,
+    forced INTEGER NOT NULL DEFAULT 0,
+    outcome TEXT NOT NULL,
+    error TEXT,
+    detail TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_process_actions_timestamp ON process_actions(timestamp);
+";
+
+// v6: 每次采样时 CPU 或内存占用最高的进程，用于回溯某个时刻是谁在占用资源
+pub const V6_PROCESS_HISTORY: &str = "
+CREATE TABLE IF NOT EXISTS process_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    pid INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    command TEXT,
+    user TEXT,
+    cpu_usage REAL NOT NULL,
+    memory_bytes INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_process_history_timestamp ON process_history(timestamp);
+CREATE INDEX IF NOT EXISTS idx_process_history_pid ON process_history(pid, timestamp);
+CREATE INDEX IF NOT EXISTS idx_process_history_name ON process_history(name, timestamp);
+";
+
+// v7: 监视规则触发记录，condition/action 保存触发时规则配置的 JSON
+pub const V7_WATCH_TRIGGERS: &str = "
+CREATE TABLE IF NOT EXISTS watch_triggers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    rule_name TEXT NOT NULL,
+    pid INTEGER NOT NULL,
+    process_name TEXT NOT NULL,
+    condition TEXT NOT NULL,
+    action TEXT NOT NULL,
+    dry_run INTEGER NOT NULL DEFAULT 0,
+    outcome TEXT NOT NULL,
+    error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_watch_triggers_timestamp ON watch_triggers(timestamp);
+";
+
+// v8: 各 cgroup（服务、容器、用户会话）的资源占用
+pub const V8_CGROUP_HISTORY: &str = "
+CREATE TABLE IF NOT EXISTS cgroup_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    cpu_percent REAL NOT NULL,
+    memory_bytes INTEGER NOT NULL,
+    io_read_speed REAL NOT NULL,
+    io_write_speed REAL NOT NULL,
+    pids INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_cgroup_history_timestamp ON cgroup_history(timestamp);
+CREATE INDEX IF NOT EXISTS idx_cgroup_history_path ON cgroup_history(path, timestamp);
+";
+
+// v9: PSI（Pressure Stall Information），原始采样按资源一行，小时/日汇总随父汇总行一起删除
+pub const V9_PRESSURE: &str = "
+CREATE TABLE IF NOT EXISTS pressure_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    resource TEXT NOT NULL,
+    some_avg10 REAL NOT NULL,
+    some_avg60 REAL NOT NULL,
+    some_avg300 REAL NOT NULL,
+    some_total_usec INTEGER NOT NULL,
+    full_avg10 REAL,
+    full_avg60 REAL,
+    full_avg300 REAL,
+    full_total_usec INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_pressure_history_timestamp ON pressure_history(timestamp);
+
+CREATE TABLE IF NOT EXISTS hourly_pressure_stats (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    hour_timestamp INTEGER NOT NULL REFERENCES hourly_stats(hour_timestamp) ON DELETE CASCADE,
+    resource TEXT NOT NULL,
+    avg_some REAL NOT NULL,
+    max_some REAL NOT NULL,
+    avg_full REAL,
+    max_full REAL,
+    some_stall_usec INTEGER NOT NULL,
+    full_stall_usec INTEGER NOT NULL,
+    UNIQUE(hour_timestamp, resource)
+);
+
+CREATE TABLE IF NOT EXISTS daily_pressure_stats (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    day_timestamp INTEGER NOT NULL REFERENCES daily_stats(day_timestamp) ON DELETE CASCADE,
+    resource TEXT NOT NULL,
+    avg_some REAL NOT NULL,
+    max_some REAL NOT NULL,
+    avg_full REAL,
+    max_full REAL,
+    some_stall_usec INTEGER NOT NULL,
+    full_stall_usec INTEGER NOT NULL,
+    UNIQUE(day_timestamp, resource)
+);
+";
+
+// v10: /proc/stat 的 CPU 时间占比与系统计数器速率，整体写入 cpu_history 新增的列，各核心一行写入 cpu_core_history。
+// 旧记录以及非 Linux 上的记录这些列为 NULL
+pub const V10_CPU_TIMES: &str = "
+ALTER TABLE cpu_history ADD COLUMN user_percent REAL;
+ALTER TABLE cpu_history ADD COLUMN nice_percent REAL;
+ALTER TABLE cpu_history ADD COLUMN system_percent REAL;
+ALTER TABLE cpu_history ADD COLUMN idle_percent REAL;
+ALTER TABLE cpu_history ADD COLUMN iowait_percent REAL;
+ALTER TABLE cpu_history ADD COLUMN irq_percent REAL;
+ALTER TABLE cpu_history ADD COLUMN softirq_percent REAL;
+ALTER TABLE cpu_history ADD COLUMN steal_percent REAL;
+ALTER TABLE cpu_history ADD COLUMN guest_percent REAL;
+ALTER TABLE cpu_history ADD COLUMN context_switches_per_sec REAL;
+ALTER TABLE cpu_history ADD COLUMN interrupts_per_sec REAL;
+ALTER TABLE cpu_history ADD COLUMN forks_per_sec REAL;
+
+CREATE TABLE IF NOT EXISTS cpu_core_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    core INTEGER NOT NULL,
+    usage REAL NOT NULL,
+    user_percent REAL NOT NULL,
+    nice_percent REAL NOT NULL,
+    system_percent REAL NOT NULL,
+    idle_percent REAL NOT NULL,
+    iowait_percent REAL NOT NULL,
+    irq_percent REAL NOT NULL,
+    softirq_percent REAL NOT NULL,
+    steal_percent REAL NOT NULL,
+    guest_percent REAL NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_cpu_core_history_timestamp ON cpu_core_history(timestamp);
+";
+
+// v11: 负载均值，旧记录和 Windows 上为 NULL
+pub const V11_LOAD_AVERAGE: &str = "
+ALTER TABLE cpu_history ADD COLUMN load_avg_1 REAL;
+ALTER TABLE cpu_history ADD COLUMN load_avg_5 REAL;
+ALTER TABLE cpu_history ADD COLUMN load_avg_15 REAL;
+";
+
+// v12: /proc/meminfo 的详细拆分，旧记录和非 Linux 上为 NULL；
+// zswap 与 zram 在内核不支持或没有 zram 设备时同样为 NULL
+pub const V12_MEMORY_BREAKDOWN: &str = "
+ALTER TABLE memory_history ADD COLUMN buffers_bytes INTEGER;
+ALTER TABLE memory_history ADD COLUMN cached_bytes INTEGER;
+ALTER TABLE memory_history ADD COLUMN shared_bytes INTEGER;
+ALTER TABLE memory_history ADD COLUMN slab_reclaimable_bytes INTEGER;
+ALTER TABLE memory_history ADD COLUMN slab_unreclaimable_bytes INTEGER;
+ALTER TABLE memory_history ADD COLUMN dirty_bytes INTEGER;
+ALTER TABLE memory_history ADD COLUMN writeback_bytes INTEGER;
+ALTER TABLE memory_history ADD COLUMN anon_hugepages_bytes INTEGER;
+ALTER TABLE memory_history ADD COLUMN hugepages_total INTEGER;
+ALTER TABLE memory_history ADD COLUMN hugepages_free INTEGER;
+ALTER TABLE memory_history ADD COLUMN hugepage_size_bytes INTEGER;
+ALTER TABLE memory_history ADD COLUMN committed_bytes INTEGER;
+ALTER TABLE memory_history ADD COLUMN commit_limit_bytes INTEGER;
+ALTER TABLE memory_history ADD COLUMN zswap_bytes INTEGER;
+ALTER TABLE memory_history ADD COLUMN zswapped_bytes INTEGER;
+ALTER TABLE memory_history ADD COLUMN zram_original_bytes INTEGER;
+ALTER TABLE memory_history ADD COLUMN zram_compressed_bytes INTEGER;
+ALTER TABLE memory_history ADD COLUMN zram_used_bytes INTEGER;
+";
+
+// v13: 汇总层记录真正的最小值；迁移前写入的汇总行为 NULL
+pub const V13_MIN_USAGE: &str = "
+ALTER TABLE hourly_stats ADD COLUMN min_cpu_usage REAL;
+ALTER TABLE hourly_stats ADD COLUMN min_memory_usage REAL;
+ALTER TABLE daily_stats ADD COLUMN min_cpu_usage REAL;
+ALTER TABLE daily_stats ADD COLUMN min_memory_usage REAL;
+";
@@ -1,10 +1,21 @@
-use crate::database::schema::SCHEMA;
+use crate::database::aggregation::{aggregate_window, upsert_stats, Tier};
+use crate::database::migrations;
+use crate::database::query::{self, HistoryBucket, ResolvedQuery};
+use crate::database::retention::{self, CleanupReport, RetentionPolicy, SETTING_RETENTION_POLICY};
+use crate::database::timezone::AggregationTimezone;
 use chrono::{Duration, Utc};
-use rusqlite::{Connection, Result as SqliteResult};
+use rusqlite::{Connection, OptionalExtension, Result as SqliteResult};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Mutex;
 
+pub const SETTING_AGGREGATION_TIMEZONE: &str = "aggregation_timezone";
+pub const WATERMARK_HOURLY: &str = "hourly";
+pub const WATERMARK_DAILY: &str = "daily";
+
+const HOUR_SECS: i64 = 3600;
+
 pub struct DatabaseService {
     conn: Mutex<Connection>,
 }
@@ -15,6 +26,40 @@ pub struct CpuHistoryRecord {
     pub usage: f32,
     pub frequency: u64,
     pub per_core: Vec<f32>,
+    // 以下来自 /proc/stat，旧记录和非 Linux 上为 None
+    #[serde(default)]
+    pub times: Option<CpuTimeBreakdown>,
+    #[serde(default)]
+    pub context_switches_per_sec: Option<f64>,
+    #[serde(default)]
+    pub interrupts_per_sec: Option<f64>,
+    #[serde(default)]
+    pub forks_per_sec: Option<f64>,
+    // 1/5/15 分钟负载均值
+    #[serde(default)]
+    pub load_avg: Option<[f64; 3]>,
+}
+
+// 各类 CPU 时间占采样间隔的百分比，合计为 100；user/nice 不含 guest
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct CpuTimeBreakdown {
+    pub user: f32,
+    pub nice: f32,
+    pub system: f32,
+    pub idle: f32,
+    pub iowait: f32,
+    pub irq: f32,
+    pub softirq: f32,
+    pub steal: f32,
+    pub guest: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CpuCoreRecord {
+    pub timestamp: i64,
+    pub core: u32,
+    pub usage: f32,
+    pub times: CpuTimeBreakdown,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -25,6 +70,32 @@ pub struct MemoryHistoryRecord {
     pub available_bytes: u64,
     pub swap_used_bytes: u64,
     pub swap_total_bytes: u64,
+    // 来自 /proc/meminfo，旧记录和非 Linux 上为 None
+    #[serde(default)]
+    pub breakdown: Option<MemoryBreakdown>,
+}
+
+// zswap 需要 5.19+ 内核，zram 只在存在 zram 设备时有值
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct MemoryBreakdown {
+    pub buffers_bytes: u64,
+    pub cached_bytes: u64,
+    pub shared_bytes: u64,
+    pub slab_reclaimable_bytes: u64,
+    pub slab_unreclaimable_bytes: u64,
+    pub dirty_bytes: u64,
+    pub writeback_bytes: u64,
+    pub anon_hugepages_bytes: u64,
+    pub hugepages_total: u64,
+    pub hugepages_free: u64,
+    pub hugepage_size_bytes: u64,
+    pub committed_bytes: u64,
+    pub commit_limit_bytes: u64,
+    pub zswap_bytes: Option<u64>,
+    pub zswapped_bytes: Option<u64>,
+    pub zram_original_bytes: Option<u64>,
+    pub zram_compressed_bytes: Option<u64>,
+    pub zram_used_bytes: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -50,8 +121,10 @@ pub struct DiskHistoryRecord {
 pub struct HourlyStats {
     pub hour_timestamp: i64,
     pub avg_cpu_usage: f32,
+    pub min_cpu_usage: Option<f32>,
     pub max_cpu_usage: f32,
     pub avg_memory_usage: f32,
+    pub min_memory_usage: Option<f32>,
     pub max_memory_usage: f32,
     pub total_rx_bytes: u64,
     pub total_tx_bytes: u64,
@@ -61,19 +134,130 @@ pub struct HourlyStats {
 pub struct DailyStats {
     pub day_timestamp: i64,
     pub avg_cpu_usage: f32,
+    pub min_cpu_usage: Option<f32>,
     pub max_cpu_usage: f32,
     pub avg_memory_usage: f32,
+    pub min_memory_usage: Option<f32>,
     pub max_memory_usage: f32,
     pub total_rx_bytes: u64,
     pub total_tx_bytes: u64,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InterfaceTrafficStats {
+    pub timestamp: i64,
+    pub interface_name: String,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProcessActionRecord {
+    pub id: i64,
+    pub timestamp: i64,
+    pub actor: String,
+    pub action: String,
+    pub signal: Option<String>,
+    pub pid: u32,
+    pub process_name: Option<String>,
+    pub command_line: Option<String>,
+    pub forced: bool,
+    pub outcome: String,
+    pub error: Option<String>,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PressureHistoryRecord {
+    pub timestamp: i64,
+    pub resource: String,
+    pub some_avg10: f64,
+    pub some_avg60: f64,
+    pub some_avg300: f64,
+    pub some_total_usec: u64,
+    pub full_avg10: Option<f64>,
+    pub full_avg60: Option<f64>,
+    pub full_avg300: Option<f64>,
+    pub full_total_usec: Option<u64>,
+}
+
+// 小时/日汇总中每种资源的 PSI
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResourcePressureStats {
+    pub timestamp: i64,
+    pub resource: String,
+    pub avg_some: f64,
+    pub max_some: f64,
+    pub avg_full: Option<f64>,
+    pub max_full: Option<f64>,
+    pub some_stall_usec: u64,
+    pub full_stall_usec: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CgroupHistoryRecord {
+    pub timestamp: i64,
+    pub path: String,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub io_read_speed: f64,
+    pub io_write_speed: f64,
+    pub pids: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WatchTriggerRecord {
+    pub id: i64,
+    pub timestamp: i64,
+    pub rule_name: String,
+    pub pid: u32,
+    pub process_name: String,
+    pub condition: String,
+    pub action: String,
+    pub dry_run: bool,
+    pub outcome: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProcessHistoryRecord {
+    pub timestamp: i64,
+    pub pid: u32,
+    pub name: String,
+    pub command: Option<String>,
+    pub user: Option<String>,
+    pub cpu_usage: f32,
+    pub memory_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProcessHistoryPoint {
+    pub timestamp: i64,
+    pub cpu_usage: f32,
+    pub memory_bytes: u64,
+}
+
+// 同一个进程（PID + 名称）的时间序列；只有进入前 N 名的采样点才有数据
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProcessHistorySeries {
+    pub pid: u32,
+    pub name: String,
+    pub command: Option<String>,
+    pub user: Option<String>,
+    pub points: Vec<ProcessHistoryPoint>,
+}
+
 impl DatabaseService {
     pub fn new(app_data_dir: PathBuf) -> SqliteResult<Self> {
         std::fs::create_dir_all(&app_data_dir).ok();
         let db_path = app_data_dir.join("data.db");
-        let conn = Connection::open(db_path)?;
-        conn.execute_batch(SCHEMA)?;
+        let mut conn = Connection::open(db_path)?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+        migrations::migrate(&mut conn, &app_data_dir.join("backups"))?;
+        // 磁盘空间不足等原因失败时不影响启动，下次启动再试
+        if let Err(e) = retention::enable_incremental_vacuum(&conn) {
+            eprintln!("database: failed to enable incremental auto_vacuum: {}", e);
+        }
         Ok(Self {
             conn: Mutex::new(conn),
         })
@@ -82,29 +266,107 @@ impl DatabaseService {
     pub fn insert_cpu_history(&self, record: &CpuHistoryRecord) -> SqliteResult<()> {
         let conn = self.conn.lock().unwrap();
         let per_core_json = serde_json::to_string(&record.per_core).unwrap_or_else(|_| "[]".to_string());
+        let times = record.times.as_ref();
         conn.execute(
-            "INSERT INTO cpu_history (timestamp, usage, frequency, per_core) VALUES (?1, ?2, ?3, ?4)",
-            [
-                &record.timestamp.to_string(),
-                &record.usage.to_string(),
-                &record.frequency.to_string(),
-                &per_core_json,
+            "INSERT INTO cpu_history (timestamp, usage, frequency, per_core,
+                user_percent, nice_percent, system_percent, idle_percent, iowait_percent,
+                irq_percent, softirq_percent, steal_percent, guest_percent,
+                context_switches_per_sec, interrupts_per_sec, forks_per_sec,
+                load_avg_1, load_avg_5, load_avg_15)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
+            rusqlite::params![
+                record.timestamp,
+                record.usage,
+                record.frequency as i64,
+                per_core_json,
+                times.map(|t| t.user),
+                times.map(|t| t.nice),
+                times.map(|t| t.system),
+                times.map(|t| t.idle),
+                times.map(|t| t.iowait),
+                times.map(|t| t.irq),
+                times.map(|t| t.softirq),
+                times.map(|t| t.steal),
+                times.map(|t| t.guest),
+                record.context_switches_per_sec,
+                record.interrupts_per_sec,
+                record.forks_per_sec,
+                record.load_avg.map(|load| load[0]),
+                record.load_avg.map(|load| load[1]),
+                record.load_avg.map(|load| load[2]),
             ],
         )?;
         Ok(())
     }
 
+    // 同一次采样的所有核心在一个事务里写入
+    pub fn insert_cpu_core_history(&self, records: &[CpuCoreRecord]) -> SqliteResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO cpu_core_history (timestamp, core, usage, user_percent, nice_percent, system_percent,
+                    idle_percent, iowait_percent, irq_percent, softirq_percent, steal_percent, guest_percent)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            )?;
+            for record in records {
+                let times = &record.times;
+                stmt.execute(rusqlite::params![
+                    record.timestamp,
+                    record.core,
+                    record.usage,
+                    times.user,
+                    times.nice,
+                    times.system,
+                    times.idle,
+                    times.iowait,
+                    times.irq,
+                    times.softirq,
+                    times.steal,
+                    times.guest,
+                ])?;
+            }
+        }
+        tx.commit()
+    }
+
     pub fn insert_memory_history(&self, record: &MemoryHistoryRecord) -> SqliteResult<()> {
         let conn = self.conn.lock().unwrap();
+        let breakdown = record.breakdown.as_ref();
+        let bytes = |field: fn(&MemoryBreakdown) -> u64| breakdown.map(|b| field(b) as i64);
+        let optional = |field: fn(&MemoryBreakdown) -> Option<u64>| breakdown.and_then(field).map(|v| v as i64);
         conn.execute(
-            "INSERT INTO memory_history (timestamp, usage_percent, used_bytes, available_bytes, swap_used_bytes, swap_total_bytes) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            [
-                &record.timestamp.to_string(),
-                &record.usage_percent.to_string(),
-                &record.used_bytes.to_string(),
-                &record.available_bytes.to_string(),
-                &record.swap_used_bytes.to_string(),
-                &record.swap_total_bytes.to_string(),
+            "INSERT INTO memory_history (timestamp, usage_percent, used_bytes, available_bytes, swap_used_bytes, swap_total_bytes,
+                buffers_bytes, cached_bytes, shared_bytes, slab_reclaimable_bytes, slab_unreclaimable_bytes,
+                dirty_bytes, writeback_bytes, anon_hugepages_bytes, hugepages_total, hugepages_free, hugepage_size_bytes,
+                committed_bytes, commit_limit_bytes, zswap_bytes, zswapped_bytes,
+                zram_original_bytes, zram_compressed_bytes, zram_used_bytes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24)",
+            rusqlite::params![
+                record.timestamp,
+                record.usage_percent,
+                record.used_bytes as i64,
+                record.available_bytes as i64,
+                record.swap_used_bytes as i64,
+                record.swap_total_bytes as i64,
+                bytes(|b| b.buffers_bytes),
+                bytes(|b| b.cached_bytes),
+                bytes(|b| b.shared_bytes),
+                bytes(|b| b.slab_reclaimable_bytes),
+                bytes(|b| b.slab_unreclaimable_bytes),
+                bytes(|b| b.dirty_bytes),
+                bytes(|b| b.writeback_bytes),
+                bytes(|b| b.anon_hugepages_bytes),
+                bytes(|b| b.hugepages_total),
+                bytes(|b| b.hugepages_free),
+                bytes(|b| b.hugepage_size_bytes),
+                bytes(|b| b.committed_bytes),
+                bytes(|b| b.commit_limit_bytes),
+                optional(|b| b.zswap_bytes),
+                optional(|b| b.zswapped_bytes),
+                optional(|b| b.zram_original_bytes),
+                optional(|b| b.zram_compressed_bytes),
+                optional(|b| b.zram_used_bytes),
             ],
         )?;
         Ok(())
@@ -141,20 +403,105 @@ impl DatabaseService {
         Ok(())
     }
 
+    // 同一次采样的所有进程在一个事务里写入
+    pub fn insert_process_history(&self, records: &[ProcessHistoryRecord]) -> SqliteResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO process_history (timestamp, pid, name, command, user, cpu_usage, memory_bytes)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            for record in records {
+                stmt.execute(rusqlite::params![
+                    record.timestamp,
+                    record.pid,
+                    record.name,
+                    record.command,
+                    record.user,
+                    record.cpu_usage,
+                    record.memory_bytes as i64,
+                ])?;
+            }
+        }
+        tx.commit()
+    }
+
     pub fn get_cpu_history(&self, hours: u32) -> SqliteResult<Vec<CpuHistoryRecord>> {
         let conn = self.conn.lock().unwrap();
         let cutoff = (Utc::now() - Duration::hours(hours as i64)).timestamp();
         let mut stmt = conn.prepare(
-            "SELECT timestamp, usage, frequency, per_core FROM cpu_history WHERE timestamp >= ?1 ORDER BY timestamp ASC"
+            "SELECT timestamp, usage, frequency, per_core,
+                user_percent, nice_percent, system_percent, idle_percent, iowait_percent,
+                irq_percent, softirq_percent, steal_percent, guest_percent,
+                context_switches_per_sec, interrupts_per_sec, forks_per_sec,
+                load_avg_1, load_avg_5, load_avg_15
+             FROM cpu_history WHERE timestamp >= ?1 ORDER BY timestamp ASC"
         )?;
         let records = stmt.query_map([cutoff], |row| {
             let per_core_json: String = row.get(3)?;
             let per_core: Vec<f32> = serde_json::from_str(&per_core_json).unwrap_or_default();
+            // 迁移前写入的记录没有时间占比
+            let times = match row.get::<_, Option<f32>>(4)? {
+                Some(user) => Some(CpuTimeBreakdown {
+                    user,
+                    nice: row.get::<_, Option<f32>>(5)?.unwrap_or(0.0),
+                    system: row.get::<_, Option<f32>>(6)?.unwrap_or(0.0),
+                    idle: row.get::<_, Option<f32>>(7)?.unwrap_or(0.0),
+                    iowait: row.get::<_, Option<f32>>(8)?.unwrap_or(0.0),
+                    irq: row.get::<_, Option<f32>>(9)?.unwrap_or(0.0),
+                    softirq: row.get::<_, Option<f32>>(10)?.unwrap_or(0.0),
+                    steal: row.get::<_, Option<f32>>(11)?.unwrap_or(0.0),
+                    guest: row.get::<_, Option<f32>>(12)?.unwrap_or(0.0),
+                }),
+                None => None,
+            };
+            let load_avg = match (row.get(16)?, row.get(17)?, row.get(18)?) {
+                (Some(one), Some(five), Some(fifteen)) => Some([one, five, fifteen]),
+                _ => None,
+            };
             Ok(CpuHistoryRecord {
                 timestamp: row.get(0)?,
                 usage: row.get(1)?,
                 frequency: row.get(2)?,
                 per_core,
+                times,
+                context_switches_per_sec: row.get(13)?,
+                interrupts_per_sec: row.get(14)?,
+                forks_per_sec: row.get(15)?,
+                load_avg,
+            })
+        })?;
+        records.collect()
+    }
+
+    // 按时间、核心编号排序；core 为 None 时返回所有核心
+    pub fn get_cpu_core_history(&self, hours: u32, core: Option<u32>) -> SqliteResult<Vec<CpuCoreRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let cutoff = (Utc::now() - Duration::hours(hours as i64)).timestamp();
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, core, usage, user_percent, nice_percent, system_percent, idle_percent,
+                iowait_percent, irq_percent, softirq_percent, steal_percent, guest_percent
+             FROM cpu_core_history
+             WHERE timestamp >= ?1 AND (?2 IS NULL OR core = ?2)
+             ORDER BY timestamp ASC, core ASC",
+        )?;
+        let records = stmt.query_map(rusqlite::params![cutoff, core], |row| {
+            Ok(CpuCoreRecord {
+                timestamp: row.get(0)?,
+                core: row.get(1)?,
+                usage: row.get(2)?,
+                times: CpuTimeBreakdown {
+                    user: row.get(3)?,
+                    nice: row.get(4)?,
+                    system: row.get(5)?,
+                    idle: row.get(6)?,
+                    iowait: row.get(7)?,
+                    irq: row.get(8)?,
+                    softirq: row.get(9)?,
+                    steal: row.get(10)?,
+                    guest: row.get(11)?,
+                },
             })
         })?;
         records.collect()
@@ -164,9 +511,42 @@ impl DatabaseService {
         let conn = self.conn.lock().unwrap();
         let cutoff = (Utc::now() - Duration::hours(hours as i64)).timestamp();
         let mut stmt = conn.prepare(
-            "SELECT timestamp, usage_percent, used_bytes, available_bytes, swap_used_bytes, swap_total_bytes FROM memory_history WHERE timestamp >= ?1 ORDER BY timestamp ASC"
+            "SELECT timestamp, usage_percent, used_bytes, available_bytes, swap_used_bytes, swap_total_bytes,
+                buffers_bytes, cached_bytes, shared_bytes, slab_reclaimable_bytes, slab_unreclaimable_bytes,
+                dirty_bytes, writeback_bytes, anon_hugepages_bytes, hugepages_total, hugepages_free, hugepage_size_bytes,
+                committed_bytes, commit_limit_bytes, zswap_bytes, zswapped_bytes,
+                zram_original_bytes, zram_compressed_bytes, zram_used_bytes
+             FROM memory_history WHERE timestamp >= ?1 ORDER BY timestamp ASC"
         )?;
         let records = stmt.query_map([cutoff], |row| {
+            let optional = |index: usize| -> SqliteResult<Option<u64>> {
+                Ok(row.get::<_, Option<i64>>(index)?.map(|value| value as u64))
+            };
+            let bytes = |index: usize| -> SqliteResult<u64> { Ok(optional(index)?.unwrap_or(0)) };
+            // 迁移前写入的记录没有拆分
+            let breakdown = match optional(6)? {
+                Some(buffers_bytes) => Some(MemoryBreakdown {
+                    buffers_bytes,
+                    cached_bytes: bytes(7)?,
+                    shared_bytes: bytes(8)?,
+                    slab_reclaimable_bytes: bytes(9)?,
+                    slab_unreclaimable_bytes: bytes(10)?,
+                    dirty_bytes: bytes(11)?,
+                    writeback_bytes: bytes(12)?,
+                    anon_hugepages_bytes: bytes(13)?,
+                    hugepages_total: bytes(14)?,
+                    hugepages_free: bytes(15)?,
+                    hugepage_size_bytes: bytes(16)?,
+                    committed_bytes: bytes(17)?,
+                    commit_limit_bytes: bytes(18)?,
+                    zswap_bytes: optional(19)?,
+                    zswapped_bytes: optional(20)?,
+                    zram_original_bytes: optional(21)?,
+                    zram_compressed_bytes: optional(22)?,
+                    zram_used_bytes: optional(23)?,
+                }),
+                None => None,
+            };
             Ok(MemoryHistoryRecord {
                 timestamp: row.get(0)?,
                 usage_percent: row.get(1)?,
@@ -174,6 +554,7 @@ impl DatabaseService {
                 available_bytes: row.get(3)?,
                 swap_used_bytes: row.get(4)?,
                 swap_total_bytes: row.get(5)?,
+                breakdown,
             })
         })?;
         records.collect()
@@ -216,84 +597,72 @@ impl DatabaseService {
         records.collect()
     }
 
-    pub fn cleanup_old_data(&self) -> SqliteResult<()> {
+    // 按保留策略删除过期数据，再回收空闲页让数据库文件真正变小
+    pub fn cleanup_old_data(&self) -> SqliteResult<CleanupReport> {
         let conn = self.conn.lock().unwrap();
-        let cutoff_30d = (Utc::now() - Duration::days(30)).timestamp();
-        let cutoff_365d = (Utc::now() - Duration::days(365)).timestamp();
-
-        conn.execute("DELETE FROM cpu_history WHERE timestamp < ?1", [cutoff_365d])?;
-        conn.execute("DELETE FROM memory_history WHERE timestamp < ?1", [cutoff_365d])?;
-        conn.execute("DELETE FROM network_history WHERE timestamp < ?1", [cutoff_365d])?;
-        conn.execute("DELETE FROM disk_history WHERE timestamp < ?1", [cutoff_365d])?;
-        conn.execute("DELETE FROM hourly_stats WHERE hour_timestamp < ?1", [cutoff_30d])?;
-        conn.execute("DELETE FROM daily_stats WHERE day_timestamp < ?1", [cutoff_365d])?;
-        Ok(())
+        let policy = read_retention_policy(&conn)?;
+        let deleted_rows = retention::enforce(&conn, &policy)?;
+        retention::compact(&conn)?;
+        Ok(CleanupReport {
+            deleted_rows,
+            database_bytes: retention::file_bytes(&conn)?,
+        })
     }
 
-    pub fn aggregate_hourly_stats(&self) -> SqliteResult<()> {
+    pub fn get_retention_policy(&self) -> SqliteResult<RetentionPolicy> {
         let conn = self.conn.lock().unwrap();
-        let now = Utc::now();
-        let hour_start = now - Duration::hours(1);
-        let hour_ts = hour_start.timestamp();
-        let now_ts = now.timestamp();
-
-        let avg_cpu: Option<f32> = conn.query_row(
-            "SELECT AVG(usage) FROM cpu_history WHERE timestamp >= ?1 AND timestamp < ?2",
-            [hour_ts, now_ts],
-            |row| row.get(0),
-        ).ok().flatten();
-
-        let max_cpu: Option<f32> = conn.query_row(
-            "SELECT MAX(usage) FROM cpu_history WHERE timestamp >= ?1 AND timestamp < ?2",
-            [hour_ts, now_ts],
-            |row| row.get(0),
-        ).ok().flatten();
-
-        let avg_memory: Option<f32> = conn.query_row(
-            "SELECT AVG(usage_percent) FROM memory_history WHERE timestamp >= ?1 AND timestamp < ?2",
-            [hour_ts, now_ts],
-            |row| row.get(0),
-        ).ok().flatten();
-
-        let max_memory: Option<f32> = conn.query_row(
-            "SELECT MAX(usage_percent) FROM memory_history WHERE timestamp >= ?1 AND timestamp < ?2",
-            [hour_ts, now_ts],
-            |row| row.get(0),
-        ).ok().flatten();
-
-        let total_rx: Option<u64> = conn.query_row(
-            "SELECT SUM(rx_bytes) FROM network_history WHERE timestamp >= ?1 AND timestamp < ?2",
-            [hour_ts, now_ts],
-            |row| row.get(0),
-        ).ok().flatten();
-
-        let total_tx: Option<u64> = conn.query_row(
-            "SELECT SUM(tx_bytes) FROM network_history WHERE timestamp >= ?1 AND timestamp < ?2",
-            [hour_ts, now_ts],
-            |row| row.get(0),
-        ).ok().flatten();
+        read_retention_policy(&conn)
+    }
 
-        conn.execute(
-            "INSERT OR REPLACE INTO hourly_stats (hour_timestamp, avg_cpu_usage, max_cpu_usage, avg_memory_usage, max_memory_usage, total_rx_bytes, total_tx_bytes) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            rusqlite::params![
-                hour_ts,
-                avg_cpu.unwrap_or(0.0),
-                max_cpu.unwrap_or(0.0),
-                avg_memory.unwrap_or(0.0),
-                max_memory.unwrap_or(0.0),
-                total_rx.unwrap_or(0),
-                total_tx.unwrap_or(0),
-            ],
-        )?;
+    pub fn set_retention_policy(&self, policy: &RetentionPolicy) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        let value = serde_json::to_string(policy).unwrap_or_default();
+        write_setting(&conn, SETTING_RETENTION_POLICY, &value)
+    }
 
-        Ok(())
+    // 按整点对齐汇总所有已结束、尚未汇总的小时，返回写入的小时数
+    pub fn aggregate_hourly_stats(&self) -> SqliteResult<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tz = read_aggregation_timezone(&conn)?;
+        let current_hour = tz.hour_start(Utc::now().timestamp());
+        let hourly_days = read_retention_policy(&conn)?.hourly_days;
+        let backfill_limit = current_hour - Duration::days(hourly_days as i64).num_seconds();
+
+        let mut hour = match read_watermark(&conn, WATERMARK_HOURLY)? {
+            Some(last) => last + HOUR_SECS,
+            None => match earliest_raw_timestamp(&conn)? {
+                Some(ts) => tz.hour_start(ts),
+                None => return Ok(0),
+            },
+        };
+        if hour < backfill_limit {
+            hour = tz.hour_start(backfill_limit);
+        }
+
+        let tx = conn.transaction()?;
+        let mut written = 0;
+        let mut last_hour = None;
+        while hour < current_hour {
+            let next = hour + HOUR_SECS;
+            if let Some(stats) = aggregate_window(&tx, hour, next)? {
+                upsert_stats(&tx, Tier::Hourly, hour, &stats)?;
+                written += 1;
+            }
+            last_hour = Some(hour);
+            hour = next;
+        }
+        if let Some(last_hour) = last_hour {
+            write_watermark(&tx, WATERMARK_HOURLY, last_hour)?;
+        }
+        tx.commit()?;
+        Ok(written)
     }
 
     pub fn get_hourly_stats(&self, days: u32) -> SqliteResult<Vec<HourlyStats>> {
         let conn = self.conn.lock().unwrap();
         let cutoff = (Utc::now() - Duration::days(days as i64)).timestamp();
         let mut stmt = conn.prepare(
-            "SELECT hour_timestamp, avg_cpu_usage, max_cpu_usage, avg_memory_usage, max_memory_usage, total_rx_bytes, total_tx_bytes FROM hourly_stats WHERE hour_timestamp >= ?1 ORDER BY hour_timestamp ASC"
+            "SELECT hour_timestamp, avg_cpu_usage, max_cpu_usage, avg_memory_usage, max_memory_usage, total_rx_bytes, total_tx_bytes, min_cpu_usage, min_memory_usage FROM hourly_stats WHERE hour_timestamp >= ?1 ORDER BY hour_timestamp ASC"
         )?;
         let records = stmt.query_map([cutoff], |row| {
             Ok(HourlyStats {
@@ -304,6 +673,8 @@ impl DatabaseService {
                 max_memory_usage: row.get(4)?,
                 total_rx_bytes: row.get(5)?,
                 total_tx_bytes: row.get(6)?,
+                min_cpu_usage: row.get(7)?,
+                min_memory_usage: row.get(8)?,
             })
         })?;
         records.collect()
@@ -313,7 +684,7 @@ impl DatabaseService {
         let conn = self.conn.lock().unwrap();
         let cutoff = (Utc::now() - Duration::days(days as i64)).timestamp();
         let mut stmt = conn.prepare(
-            "SELECT day_timestamp, avg_cpu_usage, max_cpu_usage, avg_memory_usage, max_memory_usage, total_rx_bytes, total_tx_bytes FROM daily_stats WHERE day_timestamp >= ?1 ORDER BY day_timestamp ASC"
+            "SELECT day_timestamp, avg_cpu_usage, max_cpu_usage, avg_memory_usage, max_memory_usage, total_rx_bytes, total_tx_bytes, min_cpu_usage, min_memory_usage FROM daily_stats WHERE day_timestamp >= ?1 ORDER BY day_timestamp ASC"
         )?;
         let records = stmt.query_map([cutoff], |row| {
             Ok(DailyStats {
@@ -324,8 +695,449 @@ impl DatabaseService {
                 max_memory_usage: row.get(4)?,
                 total_rx_bytes: row.get(5)?,
                 total_tx_bytes: row.get(6)?,
+                min_cpu_usage: row.get(7)?,
+                min_memory_usage: row.get(8)?,
+            })
+        })?;
+        records.collect()
+    }
+
+    pub fn get_hourly_network_stats(&self, days: u32) -> SqliteResult<Vec<InterfaceTrafficStats>> {
+        let conn = self.conn.lock().unwrap();
+        read_interface_traffic(&conn, Tier::Hourly, days)
+    }
+
+    pub fn get_hourly_pressure_stats(&self, days: u32) -> SqliteResult<Vec<ResourcePressureStats>> {
+        let conn = self.conn.lock().unwrap();
+        read_pressure_stats(&conn, Tier::Hourly, days)
+    }
+
+    pub fn get_daily_pressure_stats(&self, days: u32) -> SqliteResult<Vec<ResourcePressureStats>> {
+        let conn = self.conn.lock().unwrap();
+        read_pressure_stats(&conn, Tier::Daily, days)
+    }
+
+    pub fn get_daily_network_stats(&self, days: u32) -> SqliteResult<Vec<InterfaceTrafficStats>> {
+        let conn = self.conn.lock().unwrap();
+        read_interface_traffic(&conn, Tier::Daily, days)
+    }
+
+    pub fn query_history(&self, query: &ResolvedQuery) -> SqliteResult<Vec<HistoryBucket>> {
+        let conn = self.conn.lock().unwrap();
+        query::query(&conn, query)
+    }
+
+    pub fn insert_process_action(&self, record: &ProcessActionRecord) -> SqliteResult<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO process_actions (timestamp, actor, action, signal, pid, process_name, command_line, forced, outcome, error, detail)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            rusqlite::params![
+                record.timestamp,
+                record.actor,
+                record.action,
+                record.signal,
+                record.pid,
+                record.process_name,
+                record.command_line,
+                record.forced,
+                record.outcome,
+                record.error,
+                record.detail,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    // [start, end) 时间范围内的操作记录，最新的在前
+    pub fn get_process_actions(
+        &self,
+        start: i64,
+        end: i64,
+        outcome: Option<&str>,
+        limit: u32,
+    ) -> SqliteResult<Vec<ProcessActionRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp, actor, action, signal, pid, process_name, command_line, forced, outcome, error, detail
+             FROM process_actions
+             WHERE timestamp >= ?1 AND timestamp < ?2 AND (?3 IS NULL OR outcome = ?3)
+             ORDER BY timestamp DESC, id DESC LIMIT ?4",
+        )?;
+        let records = stmt.query_map(rusqlite::params![start, end, outcome, limit], |row| {
+            Ok(ProcessActionRecord {
+                id: row.get(0)?,
+                timestamp: row.get(1)?,
+                actor: row.get(2)?,
+                action: row.get(3)?,
+                signal: row.get(4)?,
+                pid: row.get(5)?,
+                process_name: row.get(6)?,
+                command_line: row.get(7)?,
+                forced: row.get(8)?,
+                outcome: row.get(9)?,
+                error: row.get(10)?,
+                detail: row.get(11)?,
             })
         })?;
         records.collect()
     }
-}
\ No newline at end of file
+
+    pub fn insert_pressure_history(&self, records: &[PressureHistoryRecord]) -> SqliteResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO pressure_history (timestamp, resource, some_avg10, some_avg60, some_avg300, some_total_usec, full_avg10, full_avg60, full_avg300, full_total_usec)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            )?;
+            for record in records {
+                stmt.execute(rusqlite::params![
+                    record.timestamp,
+                    record.resource,
+                    record.some_avg10,
+                    record.some_avg60,
+                    record.some_avg300,
+                    record.some_total_usec as i64,
+                    record.full_avg10,
+                    record.full_avg60,
+                    record.full_avg300,
+                    record.full_total_usec.map(|total| total as i64),
+                ])?;
+            }
+        }
+        tx.commit()
+    }
+
+    pub fn insert_cgroup_history(&self, records: &[CgroupHistoryRecord]) -> SqliteResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO cgroup_history (timestamp, path, cpu_percent, memory_bytes, io_read_speed, io_write_speed, pids)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            for record in records {
+                stmt.execute(rusqlite::params![
+                    record.timestamp,
+                    record.path,
+                    record.cpu_percent,
+                    record.memory_bytes as i64,
+                    record.io_read_speed,
+                    record.io_write_speed,
+                    record.pids as i64,
+                ])?;
+            }
+        }
+        tx.commit()
+    }
+
+    // 某个 cgroup 在 [start, end) 内的记录，按时间升序；超出 limit 时保留最新的记录
+    pub fn get_cgroup_history(
+        &self,
+        path: &str,
+        start: i64,
+        end: i64,
+        limit: u32,
+    ) -> SqliteResult<Vec<CgroupHistoryRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, path, cpu_percent, memory_bytes, io_read_speed, io_write_speed, pids
+             FROM cgroup_history
+             WHERE path = ?1 AND timestamp >= ?2 AND timestamp < ?3
+             ORDER BY timestamp DESC LIMIT ?4",
+        )?;
+        let records = stmt.query_map(rusqlite::params![path, start, end, limit], |row| {
+            Ok(CgroupHistoryRecord {
+                timestamp: row.get(0)?,
+                path: row.get(1)?,
+                cpu_percent: row.get(2)?,
+                memory_bytes: row.get::<_, i64>(3)? as u64,
+                io_read_speed: row.get(4)?,
+                io_write_speed: row.get(5)?,
+                pids: row.get::<_, i64>(6)? as u64,
+            })
+        })?;
+        let mut records = records.collect::<SqliteResult<Vec<_>>>()?;
+        records.reverse();
+        Ok(records)
+    }
+
+    pub fn insert_watch_trigger(&self, record: &WatchTriggerRecord) -> SqliteResult<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO watch_triggers (timestamp, rule_name, pid, process_name, condition, action, dry_run, outcome, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            rusqlite::params![
+                record.timestamp,
+                record.rule_name,
+                record.pid,
+                record.process_name,
+                record.condition,
+                record.action,
+                record.dry_run,
+                record.outcome,
+                record.error,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    // [start, end) 时间范围内的触发记录，可按规则名过滤，最新的在前
+    pub fn get_watch_triggers(
+        &self,
+        start: i64,
+        end: i64,
+        rule_name: Option<&str>,
+        limit: u32,
+    ) -> SqliteResult<Vec<WatchTriggerRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp, rule_name, pid, process_name, condition, action, dry_run, outcome, error
+             FROM watch_triggers
+             WHERE timestamp >= ?1 AND timestamp < ?2 AND (?3 IS NULL OR rule_name = ?3)
+             ORDER BY timestamp DESC, id DESC LIMIT ?4",
+        )?;
+        let records = stmt.query_map(rusqlite::params![start, end, rule_name, limit], |row| {
+            Ok(WatchTriggerRecord {
+                id: row.get(0)?,
+                timestamp: row.get(1)?,
+                rule_name: row.get(2)?,
+                pid: row.get(3)?,
+                process_name: row.get(4)?,
+                condition: row.get(5)?,
+                action: row.get(6)?,
+                dry_run: row.get(7)?,
+                outcome: row.get(8)?,
+                error: row.get(9)?,
+            })
+        })?;
+        records.collect()
+    }
+
+    // [start, end) 内按 PID 或进程名过滤的记录，按 (PID, 名称) 分组成时间序列；
+    // 按名称查询时同名的多个进程各自成一条序列。超出 limit 时保留最新的记录
+    pub fn get_process_history(
+        &self,
+        pid: Option<u32>,
+        name: Option<&str>,
+        start: i64,
+        end: i64,
+        limit: u32,
+    ) -> SqliteResult<Vec<ProcessHistorySeries>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, pid, name, command, user, cpu_usage, memory_bytes
+             FROM process_history
+             WHERE timestamp >= ?1 AND timestamp < ?2
+               AND (?3 IS NULL OR pid = ?3) AND (?4 IS NULL OR name = ?4)
+             ORDER BY timestamp DESC, pid DESC LIMIT ?5",
+        )?;
+        let records = stmt.query_map(rusqlite::params![start, end, pid, name, limit], |row| {
+            Ok(ProcessHistoryRecord {
+                timestamp: row.get(0)?,
+                pid: row.get(1)?,
+                name: row.get(2)?,
+                command: row.get(3)?,
+                user: row.get(4)?,
+                cpu_usage: row.get(5)?,
+                memory_bytes: row.get::<_, i64>(6)? as u64,
+            })
+        })?;
+
+        let mut records = records.collect::<SqliteResult<Vec<_>>>()?;
+        records.reverse();
+
+        let mut series: Vec<ProcessHistorySeries> = Vec::new();
+        let mut index: HashMap<(u32, String), usize> = HashMap::new();
+        for record in records {
+            let point = ProcessHistoryPoint {
+                timestamp: record.timestamp,
+                cpu_usage: record.cpu_usage,
+                memory_bytes: record.memory_bytes,
+            };
+            let i = *index.entry((record.pid, record.name.clone())).or_insert_with(|| {
+                series.push(ProcessHistorySeries {
+                    pid: record.pid,
+                    name: record.name,
+                    command: record.command,
+                    user: record.user,
+                    points: Vec::new(),
+                });
+                series.len() - 1
+            });
+            series[i].points.push(point);
+        }
+        Ok(series)
+    }
+
+    pub fn get_setting(&self, key: &str) -> SqliteResult<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        read_setting(&conn, key)
+    }
+
+    pub fn set_setting(&self, key: &str, value: &str) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        write_setting(&conn, key, value)
+    }
+
+    pub fn get_aggregation_timezone(&self) -> SqliteResult<AggregationTimezone> {
+        let conn = self.conn.lock().unwrap();
+        read_aggregation_timezone(&conn)
+    }
+
+    // 切换时区后小时与日边界都会改变，删除仍有原始数据覆盖的汇总并按新时区重建
+    pub fn set_aggregation_timezone(&self, timezone: AggregationTimezone) -> SqliteResult<()> {
+        {
+            let conn = self.conn.lock().unwrap();
+            write_setting(&conn, SETTING_AGGREGATION_TIMEZONE, &timezone.name())?;
+            if let Some(earliest) = earliest_raw_timestamp(&conn)? {
+                let cutoff = earliest - Duration::days(1).num_seconds();
+                conn.execute("DELETE FROM hourly_stats WHERE hour_timestamp >= ?1", [cutoff])?;
+                conn.execute("DELETE FROM daily_stats WHERE day_timestamp >= ?1", [cutoff])?;
+            }
+            conn.execute(
+                "DELETE FROM aggregation_watermarks WHERE name IN (?1, ?2)",
+                [WATERMARK_HOURLY, WATERMARK_DAILY],
+            )?;
+        }
+        self.aggregate_hourly_stats()?;
+        self.aggregate_daily_stats()?;
+        Ok(())
+    }
+
+    // 汇总所有尚未汇总的完整自然日（不含今天），返回写入的天数
+    pub fn aggregate_daily_stats(&self) -> SqliteResult<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tz = read_aggregation_timezone(&conn)?;
+
+        let mut date = match read_watermark(&conn, WATERMARK_DAILY)? {
+            Some(ts) => match tz.date_of(ts).succ_opt() {
+                Some(date) => date,
+                None => return Ok(0),
+            },
+            None => match earliest_raw_timestamp(&conn)? {
+                Some(ts) => tz.date_of(ts),
+                None => return Ok(0),
+            },
+        };
+        let today = tz.date_of(Utc::now().timestamp());
+
+        let tx = conn.transaction()?;
+        let mut written = 0;
+        let mut last_day = None;
+        while date < today {
+            let next = match date.succ_opt() {
+                Some(next) => next,
+                None => break,
+            };
+            let start = tz.day_start(date);
+            if let Some(stats) = aggregate_window(&tx, start, tz.day_start(next))? {
+                upsert_stats(&tx, Tier::Daily, start, &stats)?;
+                written += 1;
+            }
+            last_day = Some(start);
+            date = next;
+        }
+        if let Some(last_day) = last_day {
+            write_watermark(&tx, WATERMARK_DAILY, last_day)?;
+        }
+        tx.commit()?;
+        Ok(written)
+    }
+}
+
+fn read_setting(conn: &Connection, key: &str) -> SqliteResult<Option<String>> {
+    conn.query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| row.get(0))
+        .optional()
+}
+
+fn write_setting(conn: &Connection, key: &str, value: &str) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        [key, value],
+    )?;
+    Ok(())
+}
+
+fn read_retention_policy(conn: &Connection) -> SqliteResult<RetentionPolicy> {
+    Ok(read_setting(conn, SETTING_RETENTION_POLICY)?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+fn read_aggregation_timezone(conn: &Connection) -> SqliteResult<AggregationTimezone> {
+    Ok(read_setting(conn, SETTING_AGGREGATION_TIMEZONE)?
+        .and_then(|value| AggregationTimezone::parse(&value).ok())
+        .unwrap_or_default())
+}
+
+fn earliest_raw_timestamp(conn: &Connection) -> SqliteResult<Option<i64>> {
+    conn.query_row(
+        "SELECT MIN(ts) FROM (
+            SELECT MIN(timestamp) AS ts FROM cpu_history
+            UNION ALL SELECT MIN(timestamp) FROM memory_history
+            UNION ALL SELECT MIN(timestamp) FROM network_history
+        )",
+        [],
+        |row| row.get(0),
+    )
+}
+
+fn read_watermark(conn: &Connection, name: &str) -> SqliteResult<Option<i64>> {
+    conn.query_row(
+        "SELECT last_timestamp FROM aggregation_watermarks WHERE name = ?1",
+        [name],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+fn write_watermark(conn: &Connection, name: &str, timestamp: i64) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO aggregation_watermarks (name, last_timestamp) VALUES (?1, ?2) ON CONFLICT(name) DO UPDATE SET last_timestamp = excluded.last_timestamp",
+        rusqlite::params![name, timestamp],
+    )?;
+    Ok(())
+}
+
+fn read_interface_traffic(conn: &Connection, tier: Tier, days: u32) -> SqliteResult<Vec<InterfaceTrafficStats>> {
+    let cutoff = (Utc::now() - Duration::days(days as i64)).timestamp();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {column}, interface_name, rx_bytes, tx_bytes FROM {table} WHERE {column} >= ?1 ORDER BY {column} ASC, interface_name ASC",
+        table = tier.network_table(),
+        column = tier.column(),
+    ))?;
+    let records = stmt.query_map([cutoff], |row| {
+        Ok(InterfaceTrafficStats {
+            timestamp: row.get(0)?,
+            interface_name: row.get(1)?,
+            rx_bytes: row.get(2)?,
+            tx_bytes: row.get(3)?,
+        })
+    })?;
+    records.collect()
+}
+
+fn read_pressure_stats(conn: &Connection, tier: Tier, days: u32) -> SqliteResult<Vec<ResourcePressureStats>> {
+    let cutoff = (Utc::now() - Duration::days(days as i64)).timestamp();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {column}, resource, avg_some, max_some, avg_full, max_full, some_stall_usec, full_stall_usec
+         FROM {table} WHERE {column} >= ?1 ORDER BY {column} ASC, resource ASC",
+        table = tier.pressure_table(),
+        column = tier.column(),
+    ))?;
+    let records = stmt.query_map([cutoff], |row| {
+        Ok(ResourcePressureStats {
+            timestamp: row.get(0)?,
+            resource: row.get(1)?,
+            avg_some: row.get(2)?,
+            max_some: row.get(3)?,
+            avg_full: row.get(4)?,
+            max_full: row.get(5)?,
+            some_stall_usec: row.get::<_, i64>(6)? as u64,
+            full_stall_usec: row.get::<_, i64>(7)? as u64,
+        })
+    })?;
+    records.collect()
+}
@@ -0,0 +1,147 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, Offset, TimeZone};
+use crate::error::{DashError, DashResult};
+use chrono_tz::Tz;
+
+pub const LOCAL: &str = "local";
+
+// 聚合使用的时区，决定“一天”从何时开始
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AggregationTimezone {
+    #[default]
+    Local,
+    Named(Tz),
+}
+
+impl AggregationTimezone {
+    pub fn parse(value: &str) -> DashResult<Self> {
+        if value.eq_ignore_ascii_case(LOCAL) {
+            return Ok(AggregationTimezone::Local);
+        }
+        value
+            .parse::<Tz>()
+            .map(AggregationTimezone::Named)
+            .map_err(|_| DashError::InvalidArgument(format!("unknown timezone: {}", value)))
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            AggregationTimezone::Local => LOCAL.to_string(),
+            AggregationTimezone::Named(tz) => tz.name().to_string(),
+        }
+    }
+
+    pub fn date_of(&self, timestamp: i64) -> NaiveDate {
+        let utc = DateTime::from_timestamp(timestamp, 0).unwrap_or_default();
+        match self {
+            AggregationTimezone::Local => utc.with_timezone(&Local).date_naive(),
+            AggregationTimezone::Named(tz) => utc.with_timezone(tz).date_naive(),
+        }
+    }
+
+    // 当地整点，对 UTC+5:30 这类非整小时时区同样按当地时钟对齐
+    pub fn hour_start(&self, timestamp: i64) -> i64 {
+        timestamp - (timestamp + self.offset_secs(timestamp)).rem_euclid(3600)
+    }
+
+    fn offset_secs(&self, timestamp: i64) -> i64 {
+        let utc = DateTime::from_timestamp(timestamp, 0).unwrap_or_default();
+        let offset = match self {
+            AggregationTimezone::Local => utc.with_timezone(&Local).offset().fix(),
+            AggregationTimezone::Named(tz) => utc.with_timezone(tz).offset().fix(),
+        };
+        offset.local_minus_utc() as i64
+    }
+
+    pub fn day_start(&self, date: NaiveDate) -> i64 {
+        let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+        match self {
+            AggregationTimezone::Local => local_timestamp(&Local, midnight),
+            AggregationTimezone::Named(tz) => local_timestamp(tz, midnight),
+        }
+    }
+}
+
+// 夏令时切换可能让某个本地时刻不存在或出现两次，取最早的合法时刻
+fn local_timestamp<Z: TimeZone>(tz: &Z, local: NaiveDateTime) -> i64 {
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| tz.from_local_datetime(&(local + Duration::hours(1))).earliest())
+        .map(|dt| dt.timestamp())
+        .unwrap_or_else(|| local.and_utc().timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(name: &str) -> (AggregationTimezone, Tz) {
+        let tz: Tz = name.parse().unwrap();
+        (AggregationTimezone::Named(tz), tz)
+    }
+
+    fn local_ts(tz: &Tz, y: i32, mo: u32, d: u32, h: u32, mi: u32, s: u32) -> i64 {
+        tz.with_ymd_and_hms(y, mo, d, h, mi, s).single().unwrap().timestamp()
+    }
+
+    #[test]
+    fn hour_start_half_hour_offset() {
+        let (zone, tz) = named("Asia/Kolkata");
+        // UTC+5:30：当地 10:47 属于当地 10:00 开始的小时，而不是 UTC 整点
+        let ts = local_ts(&tz, 2024, 6, 15, 10, 47, 12);
+        let start = zone.hour_start(ts);
+        assert_eq!(start, local_ts(&tz, 2024, 6, 15, 10, 0, 0));
+        assert_eq!(start.rem_euclid(3600), 1800);
+        // 整点本身不变
+        assert_eq!(zone.hour_start(start), start);
+        assert_eq!(zone.hour_start(start - 1), start - 3600);
+    }
+
+    #[test]
+    fn hour_start_quarter_hour_offset() {
+        let (zone, tz) = named("Asia/Kathmandu");
+        let ts = local_ts(&tz, 2024, 1, 2, 23, 59, 59);
+        assert_eq!(zone.hour_start(ts), local_ts(&tz, 2024, 1, 2, 23, 0, 0));
+    }
+
+    #[test]
+    fn hour_start_across_spring_forward() {
+        let (zone, tz) = named("America/New_York");
+        // 2024-03-10 02:00 EST 跳到 03:00 EDT，当地 01:00 与 03:00 两个小时首尾相接
+        let before = local_ts(&tz, 2024, 3, 10, 1, 30, 0);
+        let after = local_ts(&tz, 2024, 3, 10, 3, 15, 0);
+        assert_eq!(zone.hour_start(before), local_ts(&tz, 2024, 3, 10, 1, 0, 0));
+        assert_eq!(zone.hour_start(after), local_ts(&tz, 2024, 3, 10, 3, 0, 0));
+        assert_eq!(zone.hour_start(after) - zone.hour_start(before), 3600);
+    }
+
+    #[test]
+    fn hour_start_across_fall_back() {
+        let (zone, tz) = named("Europe/Berlin");
+        // 2024-10-27 当地 02:00-03:00 出现两次（先 CEST 后 CET），两个小时分开统计
+        let first = tz
+            .with_ymd_and_hms(2024, 10, 27, 2, 20, 0)
+            .earliest()
+            .unwrap()
+            .timestamp();
+        let second = tz
+            .with_ymd_and_hms(2024, 10, 27, 2, 20, 0)
+            .latest()
+            .unwrap()
+            .timestamp();
+        assert_eq!(second - first, 3600);
+        assert_eq!(zone.hour_start(first), first - 20 * 60);
+        assert_eq!(zone.hour_start(second), second - 20 * 60);
+        // 切换前最后一秒仍属于第一个 02:00
+        assert_eq!(zone.hour_start(second - 20 * 60 - 1), first - 20 * 60);
+    }
+
+    #[test]
+    fn hour_start_across_half_hour_dst() {
+        // Lord Howe 夏令时只拨快半小时（+10:30 -> +11:00）
+        let (zone, tz) = named("Australia/Lord_Howe");
+        let ts = local_ts(&tz, 2024, 10, 6, 3, 10, 0);
+        assert_eq!(zone.hour_start(ts), local_ts(&tz, 2024, 10, 6, 3, 0, 0));
+        let ts = local_ts(&tz, 2024, 10, 6, 1, 50, 0);
+        assert_eq!(zone.hour_start(ts), local_ts(&tz, 2024, 10, 6, 1, 0, 0));
+    }
+}
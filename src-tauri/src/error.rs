@@ -0,0 +1,82 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::io::ErrorKind;
+
+// 所有 Tauri 命令统一返回的错误类型，前端收到 {code, message, details}
+#[derive(Debug, thiserror::Error)]
+pub enum DashError {
+    #[error("{0}")]
+    InvalidArgument(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    PermissionDenied(String),
+    #[error("refusing to signal process {pid}: {reason}")]
+    Protected {
+        pid: u32,
+        rule: &'static str,
+        reason: String,
+    },
+    #[error("{0}")]
+    Unsupported(String),
+    #[error("{0}")]
+    CommandFailed(String),
+    #[error("database error: {0}")]
+    Database(#[from] rusqlite::Error),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+pub type DashResult<T> = Result<T, DashError>;
+
+impl DashError {
+    // 稳定的机器可读错误码，前端据此区分“数据库被锁”“权限不足”等情况
+    pub fn code(&self) -> &'static str {
+        match self {
+            DashError::InvalidArgument(_) => "invalid_argument",
+            DashError::NotFound(_) => "not_found",
+            DashError::PermissionDenied(_) => "permission_denied",
+            DashError::Protected { .. } => "protected_process",
+            DashError::Unsupported(_) => "unsupported",
+            DashError::CommandFailed(_) => "command_failed",
+            DashError::Database(e) => match e {
+                rusqlite::Error::QueryReturnedNoRows => "not_found",
+                rusqlite::Error::SqliteFailure(failure, _) => match failure.code {
+                    rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked => "database_locked",
+                    rusqlite::ErrorCode::DiskFull => "disk_full",
+                    rusqlite::ErrorCode::ReadOnly | rusqlite::ErrorCode::PermissionDenied => "permission_denied",
+                    _ => "database_error",
+                },
+                _ => "database_error",
+            },
+            DashError::Io(e) => match e.kind() {
+                ErrorKind::NotFound => "not_found",
+                ErrorKind::PermissionDenied => "permission_denied",
+                _ => "io_error",
+            },
+            DashError::Serialization(_) => "serialization_error",
+        }
+    }
+
+    // 底层错误的原始描述或被触发的保护规则，便于排查
+    pub fn details(&self) -> Option<String> {
+        match self {
+            DashError::Protected { rule, .. } => Some(rule.to_string()),
+            DashError::Database(e) => Some(format!("{:?}", e)),
+            DashError::Io(e) => Some(format!("{:?}", e.kind())),
+            DashError::Serialization(e) => Some(format!("line {}, column {}", e.line(), e.column())),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for DashError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("DashError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
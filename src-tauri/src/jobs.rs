@@ -0,0 +1,38 @@
+use crate::database::DatabaseService;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+// 定时维护任务：启动时先补齐错过的汇总并清理过期数据，之后每跨过一个整点执行一次
+pub fn spawn(app: AppHandle) {
+    thread::Builder::new()
+        .name("maintenance".to_string())
+        .spawn(move || run(app))
+        .expect("Failed to spawn maintenance jobs");
+}
+
+fn run(app: AppHandle) {
+    let mut last_hour = None;
+    loop {
+        let hour = chrono::Utc::now().timestamp() / 3600;
+        if last_hour != Some(hour) {
+            run_jobs(&app.state::<DatabaseService>());
+            last_hour = Some(hour);
+        }
+        thread::sleep(CHECK_INTERVAL);
+    }
+}
+
+fn run_jobs(db: &DatabaseService) {
+    if let Err(e) = db.aggregate_hourly_stats() {
+        eprintln!("maintenance: hourly aggregation failed: {}", e);
+    }
+    if let Err(e) = db.aggregate_daily_stats() {
+        eprintln!("maintenance: daily aggregation failed: {}", e);
+    }
+    if let Err(e) = db.cleanup_old_data() {
+        eprintln!("maintenance: retention cleanup failed: {}", e);
+    }
+}
@@ -1,8 +1,20 @@
 pub mod commands;
 pub mod database;
+pub mod error;
+pub mod jobs;
+pub mod metrics;
+pub mod process;
+pub mod types;
 
 use database::DatabaseService;
-use tauri::Manager;
+use metrics::cgroup::CgroupMonitor;
+use metrics::collector::CollectorState;
+use metrics::cpu::CpuMonitor;
+use metrics::publisher::PublisherState;
+use process::monitor::ProcessMonitor;
+use process::suspend::{SuspendState, SUSPENDED_ON_EXIT_EVENT};
+use process::watch::{self, WatchEngine};
+use tauri::{Emitter, Manager};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -12,22 +24,98 @@ pub fn run() {
         .setup(|app| {
             let app_data_dir = app.path().app_data_dir().expect("Failed to get app data dir");
             let db = DatabaseService::new(app_data_dir).expect("Failed to initialize database");
+            // 采集线程会评估监视规则，需要在启动它之前注册
+            let rules = watch::load_rules(&db).unwrap_or_else(|e| {
+                eprintln!("process watch: failed to load rules: {}", e);
+                Vec::new()
+            });
+            app.manage(WatchEngine::new(rules));
             app.manage(db);
+            app.manage(CollectorState::default());
+            metrics::collector::spawn(app.handle().clone());
+            app.manage(PublisherState::default());
+            metrics::publisher::spawn(app.handle().clone());
+            jobs::spawn(app.handle().clone());
+            app.manage(ProcessMonitor::default());
+            app.manage(SuspendState::default());
+            app.manage(CgroupMonitor::default());
+            app.manage(CpuMonitor::default());
             Ok(())
         })
+        // 还有被暂停的进程时先拦下关闭，由前端弹窗让用户选择全部恢复或直接退出；
+        // 每次暂停后只拦一次，前端没有响应时再次关闭即可退出
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                let state = window.state::<SuspendState>();
+                if state.should_warn_on_exit() {
+                    api.prevent_close();
+                    let _ = window.emit(SUSPENDED_ON_EXIT_EVENT, state.list());
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
+            commands::process::list_processes,
+            commands::process::get_process_details,
             commands::process::kill_process,
+            commands::process::kill_process_tree,
+            commands::process::send_signal,
+            commands::process::terminate_process,
+            commands::process::suspend_process,
+            commands::process::resume_process,
+            commands::process::list_suspended_processes,
+            commands::process::resume_all_processes,
+            commands::process::get_process_priority,
+            commands::process::set_process_nice,
+            commands::process::set_process_io_priority,
+            commands::process::get_process_affinity,
+            commands::process::set_process_affinity,
+            commands::process::get_protected_processes,
+            commands::process::set_protected_processes,
+            commands::process::get_process_actions,
+            commands::process::get_watch_rules,
+            commands::process::set_watch_rules,
+            commands::process::get_watch_triggers,
             commands::system_info::get_detailed_system_info,
-            commands::history::save_history_data,
             commands::history::get_cpu_history,
+            commands::history::get_cpu_core_history,
             commands::history::get_memory_history,
             commands::history::get_network_history,
             commands::history::get_disk_history,
             commands::history::get_all_history,
+            commands::history::query_history,
+            commands::history::get_process_history,
             commands::history::get_stats,
             commands::history::cleanup_old_data,
-            commands::history::aggregate_hourly
+            commands::history::get_retention_policy,
+            commands::history::set_retention_policy,
+            commands::history::aggregate_hourly,
+            commands::history::aggregate_daily,
+            commands::history::get_aggregation_timezone,
+            commands::history::set_aggregation_timezone,
+            commands::cgroup::get_cgroup_tree,
+            commands::cgroup::get_cgroup_history,
+            commands::cgroup::get_pressure,
+            commands::collector::get_collector_config,
+            commands::collector::set_collector_config,
+            commands::metrics::get_cpu_stats,
+            commands::metrics::get_memory_details,
+            commands::metrics::subscribe_metrics,
+            commands::metrics::update_metrics_subscription,
+            commands::metrics::unsubscribe_metrics
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                let remaining = app.state::<SuspendState>().list();
+                if !remaining.is_empty() {
+                    let pids: Vec<String> = remaining.iter().map(|p| p.pid.to_string()).collect();
+                    eprintln!(
+                        "dashsys exiting with {} suspended process(es) still stopped: {}",
+                        remaining.len(),
+                        pids.join(", ")
+                    );
+                }
+            }
+        });
 }
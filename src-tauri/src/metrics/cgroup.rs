@@ -0,0 +1,230 @@
+use crate::database::service::CgroupHistoryRecord;
+use crate::error::{DashError, DashResult};
+use crate::metrics::pressure::{self, PressureStats};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+// 纯 v2 系统挂载在 /sys/fs/cgroup；hybrid 模式下 v2 层级在 unified 子目录
+const CGROUP_ROOTS: &[&str] = &["/sys/fs/cgroup", "/sys/fs/cgroup/unified"];
+// 历史记录只保存到 system.slice/xxx.service 这一层，更深的层级数量太多
+pub const HISTORY_MAX_DEPTH: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CgroupNode {
+    // 相对 cgroup 根的路径，根为 "/"
+    pub path: String,
+    pub name: String,
+    pub depth: u32,
+    pub cpu_usage_usec: u64,
+    pub cpu_user_usec: u64,
+    pub cpu_system_usec: u64,
+    // 单核满载为 100%，第一次读取时为 0
+    pub cpu_percent: f32,
+    // 根 cgroup 没有 memory.current / pids.current
+    pub memory_current: Option<u64>,
+    pub memory_anon: u64,
+    pub memory_file: u64,
+    pub memory_kernel: u64,
+    pub memory_shmem: u64,
+    pub io_read_bytes: u64,
+    pub io_write_bytes: u64,
+    pub io_read_speed: f64,
+    pub io_write_speed: f64,
+    pub pids_current: Option<u64>,
+    // 内核未启用 PSI 时为 None
+    pub pressure: Option<PressureStats>,
+    pub children: Vec<CgroupNode>,
+}
+
+impl CgroupNode {
+    // 先序遍历，depth 不超过 max_depth 的节点（不含根）转成历史记录
+    pub fn history_records(&self, timestamp: i64, max_depth: u32) -> Vec<CgroupHistoryRecord> {
+        let mut records = Vec::new();
+        self.collect_records(timestamp, max_depth, &mut records);
+        records
+    }
+
+    fn collect_records(&self, timestamp: i64, max_depth: u32, records: &mut Vec<CgroupHistoryRecord>) {
+        if self.depth > max_depth {
+            return;
+        }
+        if self.depth > 0 {
+            records.push(CgroupHistoryRecord {
+                timestamp,
+                path: self.path.clone(),
+                cpu_percent: self.cpu_percent,
+                memory_bytes: self.memory_current.unwrap_or(0),
+                io_read_speed: self.io_read_speed,
+                io_write_speed: self.io_write_speed,
+                pids: self.pids_current.unwrap_or(0),
+            });
+        }
+        for child in &self.children {
+            child.collect_records(timestamp, max_depth, records);
+        }
+    }
+}
+
+// 计算速率用的累计计数器：CPU 微秒、读写字节
+#[derive(Clone, Copy)]
+struct Counters {
+    cpu_usec: u64,
+    read_bytes: u64,
+    write_bytes: u64,
+}
+
+struct Snapshot {
+    taken: Instant,
+    counters: HashMap<String, Counters>,
+}
+
+// 与 ProcessMonitor 一样，速率是两次读取之间的差值
+#[derive(Default)]
+pub struct CgroupMonitor {
+    previous: Mutex<Option<Snapshot>>,
+}
+
+impl CgroupMonitor {
+    pub fn tree(&self, max_depth: Option<u32>) -> DashResult<CgroupNode> {
+        self.tree_at(&find_root()?, max_depth)
+    }
+
+    fn tree_at(&self, root: &Path, max_depth: Option<u32>) -> DashResult<CgroupNode> {
+        let now = Instant::now();
+        let mut previous = self.previous.lock().unwrap();
+        let mut counters = HashMap::new();
+        let mut node = read_node(root, root, 0, max_depth.unwrap_or(u32::MAX), &mut counters)?;
+
+        if let Some(snapshot) = previous.as_ref() {
+            let secs = now.duration_since(snapshot.taken).as_secs_f64();
+            if secs > 0.0 {
+                apply_rates(&mut node, &snapshot.counters, secs);
+            }
+        }
+        *previous = Some(Snapshot { taken: now, counters });
+        Ok(node)
+    }
+}
+
+pub fn find_root() -> DashResult<PathBuf> {
+    CGROUP_ROOTS
+        .iter()
+        .map(PathBuf::from)
+        .find(|root| root.join("cgroup.controllers").exists())
+        .ok_or_else(|| DashError::Unsupported("cgroup v2 is not mounted on this system".to_string()))
+}
+
+fn read_node(
+    root: &Path,
+    dir: &Path,
+    depth: u32,
+    max_depth: u32,
+    counters: &mut HashMap<String, Counters>,
+) -> std::io::Result<CgroupNode> {
+    // 只有根目录必须可读，子 cgroup 可能在遍历过程中被删除
+    let entries = fs::read_dir(dir)?;
+    let relative = dir.strip_prefix(root).unwrap_or(dir);
+    let path = format!("/{}", relative.to_string_lossy());
+    let cpu = read_flat_keyed(&dir.join("cpu.stat"));
+    let memory = read_flat_keyed(&dir.join("memory.stat"));
+    let (io_read_bytes, io_write_bytes) = read_io_stat(&dir.join("io.stat"));
+    let field = |map: &HashMap<String, u64>, key: &str| map.get(key).copied().unwrap_or(0);
+
+    let cpu_usage_usec = field(&cpu, "usage_usec");
+    counters.insert(
+        path.clone(),
+        Counters {
+            cpu_usec: cpu_usage_usec,
+            read_bytes: io_read_bytes,
+            write_bytes: io_write_bytes,
+        },
+    );
+
+    let mut children = Vec::new();
+    if depth < max_depth {
+        let mut dirs: Vec<PathBuf> = entries
+            .flatten()
+            .filter(|entry| entry.file_type().map(|t| t.is_dir()).unwrap_or(false))
+            .map(|entry| entry.path())
+            .collect();
+        dirs.sort();
+        for child in dirs {
+            if let Ok(node) = read_node(root, &child, depth + 1, max_depth, counters) {
+                children.push(node);
+            }
+        }
+    }
+
+    Ok(CgroupNode {
+        name: dir
+            .file_name()
+            .filter(|_| depth > 0)
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "/".to_string()),
+        path,
+        depth,
+        cpu_usage_usec,
+        cpu_user_usec: field(&cpu, "user_usec"),
+        cpu_system_usec: field(&cpu, "system_usec"),
+        cpu_percent: 0.0,
+        memory_current: read_u64(&dir.join("memory.current")),
+        memory_anon: field(&memory, "anon"),
+        memory_file: field(&memory, "file"),
+        memory_kernel: field(&memory, "kernel"),
+        memory_shmem: field(&memory, "shmem"),
+        io_read_bytes,
+        io_write_bytes,
+        io_read_speed: 0.0,
+        io_write_speed: 0.0,
+        pids_current: read_u64(&dir.join("pids.current")),
+        pressure: Some(pressure::read_cgroup_dir(dir)).filter(|stats| !stats.is_empty()),
+        children,
+    })
+}
+
+fn apply_rates(node: &mut CgroupNode, previous: &HashMap<String, Counters>, secs: f64) {
+    if let Some(before) = previous.get(&node.path) {
+        let cpu_delta = node.cpu_usage_usec.saturating_sub(before.cpu_usec) as f64;
+        node.cpu_percent = (cpu_delta / 1_000_000.0 / secs * 100.0) as f32;
+        node.io_read_speed = node.io_read_bytes.saturating_sub(before.read_bytes) as f64 / secs;
+        node.io_write_speed = node.io_write_bytes.saturating_sub(before.write_bytes) as f64 / secs;
+    }
+    for child in &mut node.children {
+        apply_rates(child, previous, secs);
+    }
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+// cpu.stat、memory.stat 等 "key value" 每行一项的文件
+fn read_flat_keyed(path: &Path) -> HashMap<String, u64> {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(' ')?;
+            Some((key.to_string(), value.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+// io.stat 每个设备一行："8:0 rbytes=1 wbytes=2 rios=3 ..."，累加所有设备
+fn read_io_stat(path: &Path) -> (u64, u64) {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let mut read_bytes = 0;
+    let mut write_bytes = 0;
+    for field in content.lines().flat_map(|line| line.split_whitespace().skip(1)) {
+        match field.split_once('=') {
+            Some(("rbytes", value)) => read_bytes += value.parse::<u64>().unwrap_or(0),
+            Some(("wbytes", value)) => write_bytes += value.parse::<u64>().unwrap_or(0),
+            _ => {}
+        }
+    }
+    (read_bytes, write_bytes)
+}
@@ -0,0 +1,185 @@
+use crate::database::DatabaseService;
+use crate::error::{DashError, DashResult};
+use crate::metrics::cgroup::{self, CgroupMonitor};
+use crate::metrics::pressure;
+use crate::metrics::sampler::{self, Sample, Sampler};
+use crate::process::monitor::ProcessMonitor;
+use crate::process::watch::{self, WatchEngine, WATCH_TRIGGERED_EVENT};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const DEFAULT_INTERVAL_SECS: u64 = 5;
+pub const MIN_INTERVAL_SECS: u64 = 1;
+pub const MAX_INTERVAL_SECS: u64 = 3600;
+pub const DEFAULT_PROCESS_TOP_N: usize = 10;
+pub const MAX_PROCESS_TOP_N: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectorConfig {
+    pub enabled: bool,
+    pub interval_secs: u64,
+    // 每次采样按 CPU 和内存各取前 N 个进程写入 process_history，0 表示不记录
+    #[serde(default = "default_process_top_n")]
+    pub process_top_n: usize,
+    // 是否把服务、容器等 cgroup 的资源占用写入 cgroup_history（仅 Linux cgroup v2）
+    #[serde(default)]
+    pub record_cgroups: bool,
+}
+
+fn default_process_top_n() -> usize {
+    DEFAULT_PROCESS_TOP_N
+}
+
+// 后台采集线程的运行参数，修改后在下一次循环生效
+pub struct CollectorState {
+    enabled: AtomicBool,
+    interval_secs: AtomicU64,
+    process_top_n: AtomicUsize,
+    record_cgroups: AtomicBool,
+}
+
+impl Default for CollectorState {
+    fn default() -> Self {
+        Self {
+            enabled: AtomicBool::new(true),
+            interval_secs: AtomicU64::new(DEFAULT_INTERVAL_SECS),
+            process_top_n: AtomicUsize::new(DEFAULT_PROCESS_TOP_N),
+            record_cgroups: AtomicBool::new(false),
+        }
+    }
+}
+
+impl CollectorState {
+    pub fn config(&self) -> CollectorConfig {
+        CollectorConfig {
+            enabled: self.enabled.load(Ordering::Relaxed),
+            interval_secs: self.interval_secs.load(Ordering::Relaxed),
+            process_top_n: self.process_top_n.load(Ordering::Relaxed),
+            record_cgroups: self.record_cgroups.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn set_config(&self, config: &CollectorConfig) -> DashResult<()> {
+        if !(MIN_INTERVAL_SECS..=MAX_INTERVAL_SECS).contains(&config.interval_secs) {
+            return Err(DashError::InvalidArgument(format!(
+                "interval_secs must be between {} and {}",
+                MIN_INTERVAL_SECS, MAX_INTERVAL_SECS
+            )));
+        }
+        if config.process_top_n > MAX_PROCESS_TOP_N {
+            return Err(DashError::InvalidArgument(format!(
+                "process_top_n must be at most {}",
+                MAX_PROCESS_TOP_N
+            )));
+        }
+        self.enabled.store(config.enabled, Ordering::Relaxed);
+        self.interval_secs.store(config.interval_secs, Ordering::Relaxed);
+        self.process_top_n.store(config.process_top_n, Ordering::Relaxed);
+        self.record_cgroups.store(config.record_cgroups, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs.load(Ordering::Relaxed))
+    }
+}
+
+pub fn spawn(app: AppHandle) {
+    thread::Builder::new()
+        .name("history-collector".to_string())
+        .spawn(move || run(app))
+        .expect("Failed to spawn history collector");
+}
+
+fn run(app: AppHandle) {
+    let mut sampler = Sampler::new();
+    // 独立于进程列表命令的监视器，两边的 CPU% 采样间隔互不干扰
+    let processes = ProcessMonitor::default();
+    let _ = processes.list();
+    let cgroups = CgroupMonitor::default();
+    let mut next_tick = Instant::now();
+
+    loop {
+        let state = app.state::<CollectorState>();
+        next_tick += state.interval();
+        let now = Instant::now();
+        if next_tick > now {
+            thread::sleep(next_tick - now);
+        } else {
+            // 落后太多（例如系统休眠）时不补采，直接从当前时刻重新计时
+            next_tick = now;
+        }
+
+        // 关闭历史采集时监视规则照常按采集间隔评估
+        let config = state.config();
+        let engine = app.state::<WatchEngine>();
+        let record_processes = config.enabled && config.process_top_n > 0;
+        if !config.enabled && !engine.has_enabled_rules() {
+            continue;
+        }
+
+        let db = app.state::<DatabaseService>();
+        let timestamp = chrono::Utc::now().timestamp();
+        // 列举失败（如非 Linux）时既不记录也不评估，以免把所有进程当成已退出
+        let process_list = if record_processes || engine.has_enabled_rules() {
+            processes.list().ok()
+        } else {
+            None
+        };
+
+        if config.enabled {
+            let sample = sampler.sample();
+            if let Err(e) = write_sample(&db, &sample) {
+                eprintln!("history collector: failed to write sample: {}", e);
+            }
+        }
+        if config.enabled {
+            // 内核不支持 PSI 时静默跳过
+            if let Ok(stats) = pressure::read_system() {
+                if let Err(e) = db.insert_pressure_history(&stats.history_records(timestamp)) {
+                    eprintln!("history collector: failed to write pressure history: {}", e);
+                }
+            }
+        }
+        if config.enabled && config.record_cgroups {
+            // 没有 cgroup v2 时静默跳过
+            if let Ok(tree) = cgroups.tree(Some(cgroup::HISTORY_MAX_DEPTH)) {
+                let records = tree.history_records(timestamp, cgroup::HISTORY_MAX_DEPTH);
+                if let Err(e) = db.insert_cgroup_history(&records) {
+                    eprintln!("history collector: failed to write cgroup history: {}", e);
+                }
+            }
+        }
+        let Some(process_list) = process_list else {
+            continue;
+        };
+        if record_processes {
+            let top = sampler::top_processes(&process_list, config.process_top_n, timestamp);
+            if let Err(e) = db.insert_process_history(&top) {
+                eprintln!("history collector: failed to write process history: {}", e);
+            }
+        }
+        for trigger in engine.evaluate(&process_list, timestamp) {
+            let record = watch::execute(&db, &trigger, timestamp);
+            let _ = app.emit(WATCH_TRIGGERED_EVENT, &record);
+        }
+    }
+}
+
+fn write_sample(db: &DatabaseService, sample: &Sample) -> rusqlite::Result<()> {
+    db.insert_cpu_history(&sample.cpu)?;
+    if !sample.cores.is_empty() {
+        db.insert_cpu_core_history(&sample.cores)?;
+    }
+    db.insert_memory_history(&sample.memory)?;
+    for network in &sample.networks {
+        db.insert_network_history(network)?;
+    }
+    for disk in &sample.disks {
+        db.insert_disk_history(disk)?;
+    }
+    Ok(())
+}
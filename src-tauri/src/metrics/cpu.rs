@@ -0,0 +1,393 @@
+use crate::database::service::CpuTimeBreakdown;
+use crate::types::{CoreFrequency, CpuStats};
+use std::fs;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use sysinfo::System;
+
+// /proc/stat 中一个 cpu 行的累计时钟滴答数
+#[derive(Debug, Clone, Copy, Default)]
+struct CpuTicks {
+    user: u64,
+    nice: u64,
+    system: u64,
+    idle: u64,
+    iowait: u64,
+    irq: u64,
+    softirq: u64,
+    steal: u64,
+    guest: u64,
+    guest_nice: u64,
+}
+
+impl CpuTicks {
+    fn parse(fields: &[&str]) -> Option<Self> {
+        let field = |index: usize| -> Option<u64> {
+            // 旧内核没有 steal/guest 等靠后的列
+            match fields.get(index) {
+                Some(value) => value.parse().ok(),
+                None => Some(0),
+            }
+        };
+        Some(Self {
+            user: field(0)?,
+            nice: field(1)?,
+            system: field(2)?,
+            idle: field(3)?,
+            iowait: field(4)?,
+            irq: field(5)?,
+            softirq: field(6)?,
+            steal: field(7)?,
+            guest: field(8)?,
+            guest_nice: field(9)?,
+        })
+    }
+
+    // guest 时间已经计入 user/nice，总数里不再重复累加
+    fn total(&self) -> u64 {
+        self.user + self.nice + self.system + self.idle + self.iowait + self.irq + self.softirq + self.steal
+    }
+
+    // 与 mpstat 一致：user/nice 扣除 guest 部分，各项之和为 100
+    fn breakdown(&self, previous: &CpuTicks) -> CpuTimeBreakdown {
+        let delta = |current: u64, before: u64| current.saturating_sub(before) as f64;
+        let total = delta(self.total(), previous.total());
+        let percent = |value: f64| if total > 0.0 { (value / total * 100.0) as f32 } else { 0.0 };
+        let guest = delta(self.guest, previous.guest);
+        let guest_nice = delta(self.guest_nice, previous.guest_nice);
+        CpuTimeBreakdown {
+            user: percent((delta(self.user, previous.user) - guest).max(0.0)),
+            nice: percent((delta(self.nice, previous.nice) - guest_nice).max(0.0)),
+            system: percent(delta(self.system, previous.system)),
+            idle: percent(delta(self.idle, previous.idle)),
+            iowait: percent(delta(self.iowait, previous.iowait)),
+            irq: percent(delta(self.irq, previous.irq)),
+            softirq: percent(delta(self.softirq, previous.softirq)),
+            steal: percent(delta(self.steal, previous.steal)),
+            guest: percent(guest + guest_nice),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct StatSnapshot {
+    total: CpuTicks,
+    cores: Vec<(u32, CpuTicks)>,
+    context_switches: u64,
+    interrupts: u64,
+    forks: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct CpuStatSample {
+    pub total: CpuTimeBreakdown,
+    // (核心编号, 时间占比)，离线的核心不出现
+    pub cores: Vec<(u32, CpuTimeBreakdown)>,
+    pub context_switches_per_sec: f64,
+    pub interrupts_per_sec: f64,
+    pub forks_per_sec: f64,
+}
+
+// 百分比和速率都是两次采样之间的差值；没有 /proc/stat（非 Linux）时始终为 None
+#[derive(Default)]
+pub struct CpuStatSampler {
+    previous: Option<(Instant, StatSnapshot)>,
+}
+
+impl CpuStatSampler {
+    pub fn new() -> Self {
+        Self {
+            previous: read_snapshot().map(|snapshot| (Instant::now(), snapshot)),
+        }
+    }
+
+    pub fn sample(&mut self) -> Option<CpuStatSample> {
+        let current = read_snapshot()?;
+        let now = Instant::now();
+        let previous = self.previous.replace((now, current.clone()));
+        let (taken, previous) = previous?;
+        let secs = now.duration_since(taken).as_secs_f64();
+        if secs <= 0.0 {
+            return None;
+        }
+        let rate = |current: u64, before: u64| current.saturating_sub(before) as f64 / secs;
+
+        Some(CpuStatSample {
+            total: current.total.breakdown(&previous.total),
+            cores: current
+                .cores
+                .iter()
+                .filter_map(|(core, ticks)| {
+                    let (_, before) = previous.cores.iter().find(|(id, _)| id == core)?;
+                    Some((*core, ticks.breakdown(before)))
+                })
+                .collect(),
+            context_switches_per_sec: rate(current.context_switches, previous.context_switches),
+            interrupts_per_sec: rate(current.interrupts, previous.interrupts),
+            forks_per_sec: rate(current.forks, previous.forks),
+        })
+    }
+}
+
+fn read_snapshot() -> Option<StatSnapshot> {
+    parse_snapshot(&fs::read_to_string("/proc/stat").ok()?)
+}
+
+fn parse_snapshot(content: &str) -> Option<StatSnapshot> {
+    let mut snapshot = StatSnapshot::default();
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let Some(key) = fields.next() else {
+            continue;
+        };
+        let values: Vec<&str> = fields.collect();
+        match key {
+            "cpu" => snapshot.total = CpuTicks::parse(&values)?,
+            "ctxt" => snapshot.context_switches = values.first()?.parse().ok()?,
+            // intr 第一列是总数，后面是各中断号的计数
+            "intr" => snapshot.interrupts = values.first()?.parse().ok()?,
+            "processes" => snapshot.forks = values.first()?.parse().ok()?,
+            _ => {
+                if let Some(core) = key.strip_prefix("cpu").and_then(|id| id.parse().ok()) {
+                    if let Some(ticks) = CpuTicks::parse(&values) {
+                        snapshot.cores.push((core, ticks));
+                    }
+                }
+            }
+        }
+    }
+    Some(snapshot)
+}
+
+const CPUFREQ_ROOT: &str = "/sys/devices/system/cpu";
+// 每个核心要读好几个 cpufreq 文件，推送间隔可短至 250ms，频率按较慢的节奏刷新
+const FREQUENCY_REFRESH: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy)]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+    pub running_tasks: Option<u32>,
+    pub total_tasks: Option<u32>,
+}
+
+impl LoadAverage {
+    pub fn as_array(&self) -> [f64; 3] {
+        [self.one, self.five, self.fifteen]
+    }
+}
+
+// 与 ProcessMonitor 一样，使用率是两次调用之间的平均值；启动时先刷新一次
+pub struct CpuMonitor {
+    system: Mutex<System>,
+}
+
+impl Default for CpuMonitor {
+    fn default() -> Self {
+        let mut system = System::new();
+        system.refresh_cpu();
+        Self {
+            system: Mutex::new(system),
+        }
+    }
+}
+
+impl CpuMonitor {
+    pub fn stats(&self) -> CpuStats {
+        let mut system = self.system.lock().unwrap();
+        system.refresh_cpu();
+        let cpus = system.cpus();
+        cpu_stats(
+            system.global_cpu_info().cpu_usage(),
+            cpus.first().map(|c| c.frequency()).unwrap_or(0),
+            cpus.iter().map(|c| c.cpu_usage()).collect(),
+            read_frequencies(),
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct FrequencyCache {
+    cached: Option<(Instant, Vec<CoreFrequency>)>,
+}
+
+impl FrequencyCache {
+    pub fn get(&mut self) -> Vec<CoreFrequency> {
+        match &self.cached {
+            Some((taken, frequencies)) if taken.elapsed() < FREQUENCY_REFRESH => frequencies.clone(),
+            _ => {
+                let frequencies = read_frequencies();
+                self.cached = Some((Instant::now(), frequencies.clone()));
+                frequencies
+            }
+        }
+    }
+}
+
+pub fn cpu_stats(usage: f32, frequency: u64, per_core: Vec<f32>, frequencies: Vec<CoreFrequency>) -> CpuStats {
+    let load = load_average();
+    CpuStats {
+        usage,
+        frequency,
+        core_count: per_core.len(),
+        per_core,
+        load_avg: load.map(|load| load.as_array()).unwrap_or_default(),
+        running_tasks: load.and_then(|load| load.running_tasks),
+        total_tasks: load.and_then(|load| load.total_tasks),
+        frequencies,
+    }
+}
+
+// 格式：0.52 0.58 0.59 2/1024 12345（第四列为 可运行/总数）
+#[cfg(target_os = "linux")]
+pub fn load_average() -> Option<LoadAverage> {
+    let content = fs::read_to_string("/proc/loadavg").ok()?;
+    let fields: Vec<&str> = content.split_whitespace().collect();
+    let (running, total) = fields.get(3)?.split_once('/')?;
+    Some(LoadAverage {
+        one: fields.first()?.parse().ok()?,
+        five: fields.get(1)?.parse().ok()?,
+        fifteen: fields.get(2)?.parse().ok()?,
+        running_tasks: running.parse().ok(),
+        total_tasks: total.parse().ok(),
+    })
+}
+
+// Windows 没有负载均值
+#[cfg(windows)]
+pub fn load_average() -> Option<LoadAverage> {
+    None
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+pub fn load_average() -> Option<LoadAverage> {
+    let load = System::load_average();
+    Some(LoadAverage {
+        one: load.one,
+        five: load.five,
+        fifteen: load.fifteen,
+        running_tasks: None,
+        total_tasks: None,
+    })
+}
+
+// cpufreq 的数值单位是 kHz；核心离线或没有 cpufreq 目录时跳过
+pub fn read_frequencies() -> Vec<CoreFrequency> {
+    let Ok(entries) = fs::read_dir(CPUFREQ_ROOT) else {
+        return Vec::new();
+    };
+    let mut frequencies: Vec<CoreFrequency> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let core = name.to_str()?.strip_prefix("cpu")?.parse().ok()?;
+            let dir = entry.path().join("cpufreq");
+            let read_mhz = |file: &str| -> Option<u64> {
+                let khz: u64 = fs::read_to_string(dir.join(file)).ok()?.trim().parse().ok()?;
+                Some(khz / 1000)
+            };
+            Some(CoreFrequency {
+                core,
+                current: read_mhz("scaling_cur_freq")?,
+                min: read_mhz("cpuinfo_min_freq").unwrap_or(0),
+                max: read_mhz("cpuinfo_max_freq").unwrap_or(0),
+                governor: fs::read_to_string(dir.join("scaling_governor"))
+                    .ok()
+                    .map(|governor| governor.trim().to_string()),
+            })
+        })
+        .collect();
+    frequencies.sort_by_key(|frequency| frequency.core);
+    frequencies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 两次相隔一秒的 /proc/stat，cpu1 来自只有前 7 列的旧内核
+    const BEFORE: &str = "cpu  1000 200 300 5000 100 10 20 30 400 100
+cpu0 600 150 200 2400 50 5 10 30 400 100
+cpu1 400 50 100 2600 50 5 10
+intr 9000 1 2 3
+ctxt 50000
+processes 700
+";
+    const AFTER: &str = "cpu  1600 300 400 5900 150 20 30 100 500 150
+cpu0 1100 240 260 2500 80 10 15 100 500 150
+cpu1 500 60 140 3400 70 10 15
+intr 9800 1 2 3
+ctxt 53000
+processes 710
+";
+
+    fn snapshots() -> (StatSnapshot, StatSnapshot) {
+        (parse_snapshot(BEFORE).unwrap(), parse_snapshot(AFTER).unwrap())
+    }
+
+    fn sum(breakdown: &CpuTimeBreakdown) -> f32 {
+        breakdown.user
+            + breakdown.nice
+            + breakdown.system
+            + breakdown.idle
+            + breakdown.iowait
+            + breakdown.irq
+            + breakdown.softirq
+            + breakdown.steal
+            + breakdown.guest
+    }
+
+    #[test]
+    fn parse_snapshot_reads_counters_and_cores() {
+        let (before, _) = snapshots();
+        assert_eq!(before.total.user, 1000);
+        assert_eq!(before.total.guest_nice, 100);
+        assert_eq!(before.cores.len(), 2);
+        assert_eq!(before.context_switches, 50_000);
+        assert_eq!(before.interrupts, 9_000);
+        assert_eq!(before.forks, 700);
+    }
+
+    #[test]
+    fn breakdown_subtracts_guest_time() {
+        let (before, after) = snapshots();
+        // total 增量：600+100+100+900+50+10+10+70 = 1840，guest 100、guest_nice 50
+        let breakdown = after.total.breakdown(&before.total);
+        let percent = |ticks: f32| ticks / 1840.0 * 100.0;
+        assert!((breakdown.user - percent(500.0)).abs() < 1e-4);
+        assert!((breakdown.nice - percent(50.0)).abs() < 1e-4);
+        assert!((breakdown.guest - percent(150.0)).abs() < 1e-4);
+        assert!((breakdown.steal - percent(70.0)).abs() < 1e-4);
+        assert!((sum(&breakdown) - 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn breakdown_handles_short_rows() {
+        let (before, after) = snapshots();
+        let (_, cpu1_before) = before.cores[1];
+        let (_, cpu1_after) = after.cores[1];
+        assert_eq!(cpu1_after.steal, 0);
+        assert_eq!(cpu1_after.guest, 0);
+        let breakdown = cpu1_after.breakdown(&cpu1_before);
+        // 增量：100+10+40+800+20+5+5 = 980
+        assert!((breakdown.idle - 800.0 / 980.0 * 100.0).abs() < 1e-4);
+        assert_eq!(breakdown.steal, 0.0);
+        assert_eq!(breakdown.guest, 0.0);
+        assert!((sum(&breakdown) - 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn breakdown_sums_to_100_per_core() {
+        let (before, after) = snapshots();
+        for ((_, now), (_, then)) in after.cores.iter().zip(&before.cores) {
+            assert!((sum(&now.breakdown(then)) - 100.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn breakdown_without_elapsed_ticks_is_zero() {
+        let (before, _) = snapshots();
+        assert_eq!(sum(&before.total.breakdown(&before.total)), 0.0);
+    }
+}
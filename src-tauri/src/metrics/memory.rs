@@ -0,0 +1,210 @@
+use crate::database::service::MemoryBreakdown;
+use crate::error::{DashError, DashResult};
+use crate::types::{MemoryDetails, ZramDevice};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const SYS_BLOCK_DIR: &str = "/sys/block";
+// zram 设备很少增减，周期采样时只按这个间隔重新扫描 /sys/block
+const ZRAM_RESCAN: Duration = Duration::from_secs(60);
+
+pub fn read_details() -> DashResult<MemoryDetails> {
+    let meminfo = read_meminfo()?;
+    let field = |key: &str| meminfo.get(key).copied().unwrap_or(0);
+    let zram_devices = read_zram_devices();
+
+    Ok(MemoryDetails {
+        total: field("MemTotal"),
+        free: field("MemFree"),
+        available: field("MemAvailable"),
+        swap_total: field("SwapTotal"),
+        swap_free: field("SwapFree"),
+        breakdown: breakdown(&meminfo, &zram_devices),
+        zram_devices,
+    })
+}
+
+// 周期采样使用：zram 设备列表走缓存，每次只读取各设备的统计
+pub fn read_breakdown(zram: &mut ZramCache) -> DashResult<MemoryBreakdown> {
+    Ok(breakdown(&read_meminfo()?, &zram.devices()))
+}
+
+#[derive(Default)]
+pub struct ZramCache {
+    scanned: Option<(Instant, Vec<String>)>,
+}
+
+impl ZramCache {
+    pub fn devices(&mut self) -> Vec<ZramDevice> {
+        let names = match &self.scanned {
+            Some((taken, names)) if taken.elapsed() < ZRAM_RESCAN => names,
+            _ => &self.scanned.insert((Instant::now(), zram_device_names())).1,
+        };
+        names
+            .iter()
+            .filter_map(|name| read_zram_device(&Path::new(SYS_BLOCK_DIR).join(name), name.clone()))
+            .collect()
+    }
+}
+
+fn read_meminfo() -> DashResult<HashMap<String, u64>> {
+    let content = fs::read_to_string("/proc/meminfo")
+        .map_err(|_| DashError::Unsupported("/proc/meminfo is not available on this system".to_string()))?;
+    Ok(parse_meminfo(&content))
+}
+
+fn breakdown(meminfo: &HashMap<String, u64>, zram_devices: &[ZramDevice]) -> MemoryBreakdown {
+    let field = |key: &str| meminfo.get(key).copied().unwrap_or(0);
+    // 没有 zram 设备时三项都是 None，而不是 0
+    let zram_total = |value: fn(&ZramDevice) -> u64| {
+        Some(zram_devices.iter().map(value).sum()).filter(|_| !zram_devices.is_empty())
+    };
+
+    MemoryBreakdown {
+        buffers_bytes: field("Buffers"),
+        cached_bytes: field("Cached"),
+        shared_bytes: field("Shmem"),
+        slab_reclaimable_bytes: field("SReclaimable"),
+        slab_unreclaimable_bytes: field("SUnreclaim"),
+        dirty_bytes: field("Dirty"),
+        writeback_bytes: field("Writeback"),
+        anon_hugepages_bytes: field("AnonHugePages"),
+        hugepages_total: field("HugePages_Total"),
+        hugepages_free: field("HugePages_Free"),
+        hugepage_size_bytes: field("Hugepagesize"),
+        committed_bytes: field("Committed_AS"),
+        commit_limit_bytes: field("CommitLimit"),
+        zswap_bytes: meminfo.get("Zswap").copied(),
+        zswapped_bytes: meminfo.get("Zswapped").copied(),
+        zram_original_bytes: zram_total(|device| device.original_bytes),
+        zram_compressed_bytes: zram_total(|device| device.compressed_bytes),
+        zram_used_bytes: zram_total(|device| device.memory_used),
+    }
+}
+
+// 格式："Buffers:   87728 kB"；带 kB 的换算成字节，HugePages_* 等没有单位的是个数
+fn parse_meminfo(content: &str) -> HashMap<String, u64> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let mut fields = rest.split_whitespace();
+            let value: u64 = fields.next()?.parse().ok()?;
+            let value = match fields.next() {
+                Some("kB") => value * 1024,
+                _ => value,
+            };
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+fn read_zram_devices() -> Vec<ZramDevice> {
+    zram_device_names()
+        .into_iter()
+        .filter_map(|name| read_zram_device(&Path::new(SYS_BLOCK_DIR).join(&name), name))
+        .collect()
+}
+
+fn zram_device_names() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(SYS_BLOCK_DIR) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("zram"))
+        .collect();
+    names.sort();
+    names
+}
+
+// 未初始化（disksize 为 0）的设备跳过
+fn read_zram_device(dir: &Path, name: String) -> Option<ZramDevice> {
+    let disk_size: u64 = fs::read_to_string(dir.join("disksize")).ok()?.trim().parse().ok()?;
+    if disk_size == 0 {
+        return None;
+    }
+    // mm_stat：orig_data_size compr_data_size mem_used_total mem_limit mem_used_max ...
+    let mm_stat = fs::read_to_string(dir.join("mm_stat")).unwrap_or_default();
+    let stats: Vec<u64> = mm_stat
+        .split_whitespace()
+        .map(|value| value.parse().unwrap_or(0))
+        .collect();
+    let stat = |index: usize| stats.get(index).copied().unwrap_or(0);
+    let algorithm = fs::read_to_string(dir.join("comp_algorithm"))
+        .ok()
+        .and_then(|content| parse_algorithm(&content));
+
+    Some(ZramDevice {
+        name,
+        disk_size,
+        original_bytes: stat(0),
+        compressed_bytes: stat(1),
+        memory_used: stat(2),
+        memory_limit: Some(stat(3)).filter(|limit| *limit > 0),
+        algorithm,
+    })
+}
+
+// comp_algorithm 列出所有可用算法，当前使用的用方括号标出："lzo [lz4] zstd"
+fn parse_algorithm(content: &str) -> Option<String> {
+    content
+        .split_whitespace()
+        .find_map(|algorithm| algorithm.strip_prefix('[')?.strip_suffix(']').map(str::to_string))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEMINFO: &str = "MemTotal:       16318036 kB
+MemFree:         1203140 kB
+Buffers:           87728 kB
+Cached:          6712660 kB
+HugePages_Total:       4
+HugePages_Free:        2
+Hugepagesize:       2048 kB
+";
+
+    #[test]
+    fn parse_meminfo_converts_kb() {
+        let meminfo = parse_meminfo(MEMINFO);
+        assert_eq!(meminfo["MemTotal"], 16_318_036 * 1024);
+        assert_eq!(meminfo["Buffers"], 87_728 * 1024);
+        assert_eq!(meminfo["Hugepagesize"], 2 * 1024 * 1024);
+    }
+
+    #[test]
+    fn parse_meminfo_keeps_unitless_counts() {
+        let meminfo = parse_meminfo(MEMINFO);
+        assert_eq!(meminfo["HugePages_Total"], 4);
+        assert_eq!(meminfo["HugePages_Free"], 2);
+    }
+
+    #[test]
+    fn breakdown_without_zswap_or_zram() {
+        let without = breakdown(&parse_meminfo(MEMINFO), &[]);
+        assert_eq!(without.cached_bytes, 6_712_660 * 1024);
+        assert_eq!(without.hugepages_total, 4);
+        // 旧内核没有 Zswap 行时为 None，而不是 0
+        assert_eq!(without.zswap_bytes, None);
+        assert_eq!(without.zswapped_bytes, None);
+        assert_eq!(without.zram_used_bytes, None);
+
+        let with_zswap = format!("{}Zswap:              1024 kB\nZswapped:           4096 kB\n", MEMINFO);
+        let with = breakdown(&parse_meminfo(&with_zswap), &[]);
+        assert_eq!(with.zswap_bytes, Some(1024 * 1024));
+        assert_eq!(with.zswapped_bytes, Some(4096 * 1024));
+    }
+
+    #[test]
+    fn parse_algorithm_picks_bracketed() {
+        assert_eq!(parse_algorithm("lzo lzo-rle [lz4] lz4hc zstd\n").as_deref(), Some("lz4"));
+        assert_eq!(parse_algorithm("[zstd]").as_deref(), Some("zstd"));
+        assert_eq!(parse_algorithm("lzo lz4 zstd"), None);
+        assert_eq!(parse_algorithm(""), None);
+    }
+}
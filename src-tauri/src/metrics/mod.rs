@@ -0,0 +1,7 @@
+pub mod cgroup;
+pub mod collector;
+pub mod cpu;
+pub mod memory;
+pub mod pressure;
+pub mod publisher;
+pub mod sampler;
@@ -0,0 +1,128 @@
+use crate::database::service::PressureHistoryRecord;
+use crate::error::{DashError, DashResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const PROC_PRESSURE_DIR: &str = "/proc/pressure";
+
+// PSI 的一行：最近 10/60/300 秒内停顿时间的百分比，以及累计停顿微秒数
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PressureLine {
+    pub avg10: f64,
+    pub avg60: f64,
+    pub avg300: f64,
+    pub total_usec: u64,
+}
+
+// some: 至少有一个任务在等待该资源；full: 所有非空闲任务同时在等待。
+// 系统级 CPU 的 full 行在 5.13 之前的内核上不存在
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourcePressure {
+    pub some: PressureLine,
+    pub full: Option<PressureLine>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PressureStats {
+    pub cpu: Option<ResourcePressure>,
+    pub memory: Option<ResourcePressure>,
+    pub io: Option<ResourcePressure>,
+}
+
+impl PressureStats {
+    pub fn is_empty(&self) -> bool {
+        self.cpu.is_none() && self.memory.is_none() && self.io.is_none()
+    }
+
+    pub fn history_records(&self, timestamp: i64) -> Vec<PressureHistoryRecord> {
+        [("cpu", &self.cpu), ("memory", &self.memory), ("io", &self.io)]
+            .into_iter()
+            .filter_map(|(resource, pressure)| {
+                let pressure = pressure.as_ref()?;
+                Some(PressureHistoryRecord {
+                    timestamp,
+                    resource: resource.to_string(),
+                    some_avg10: pressure.some.avg10,
+                    some_avg60: pressure.some.avg60,
+                    some_avg300: pressure.some.avg300,
+                    some_total_usec: pressure.some.total_usec,
+                    full_avg10: pressure.full.map(|full| full.avg10),
+                    full_avg60: pressure.full.map(|full| full.avg60),
+                    full_avg300: pressure.full.map(|full| full.avg300),
+                    full_total_usec: pressure.full.map(|full| full.total_usec),
+                })
+            })
+            .collect()
+    }
+}
+
+// 需要内核开启 CONFIG_PSI（4.20+），部分发行版还需要启动参数 psi=1
+pub fn read_system() -> DashResult<PressureStats> {
+    let stats = read_dir(Path::new(PROC_PRESSURE_DIR), "");
+    if stats.is_empty() {
+        return Err(DashError::Unsupported(
+            "Pressure stall information is not available on this system".to_string(),
+        ));
+    }
+    Ok(stats)
+}
+
+// cgroup v2 目录下的 cpu.pressure / memory.pressure / io.pressure，
+// 没有启用 PSI 时相应项为 None
+pub fn read_cgroup_dir(dir: &Path) -> PressureStats {
+    read_dir(dir, ".pressure")
+}
+
+// path 为相对 cgroup 根的路径，与 CgroupNode::path 相同
+pub fn read_cgroup(path: &str) -> DashResult<PressureStats> {
+    let relative = path.trim_start_matches('/');
+    if relative.split('/').any(|part| part == "..") {
+        return Err(DashError::InvalidArgument(format!("invalid cgroup path: {}", path)));
+    }
+    let dir = crate::metrics::cgroup::find_root()?.join(relative);
+    if !dir.is_dir() {
+        return Err(DashError::NotFound(format!("cgroup {} not found", path)));
+    }
+    let stats = read_cgroup_dir(&dir);
+    if stats.is_empty() {
+        return Err(DashError::Unsupported(format!("pressure stall information is not available for cgroup {}", path)));
+    }
+    Ok(stats)
+}
+
+fn read_dir(dir: &Path, suffix: &str) -> PressureStats {
+    let read = |resource: &str| read_file(&dir.join(format!("{}{}", resource, suffix)));
+    PressureStats {
+        cpu: read("cpu"),
+        memory: read("memory"),
+        io: read("io"),
+    }
+}
+
+// 格式：some avg10=0.00 avg60=0.00 avg300=0.00 total=0
+fn read_file(path: &Path) -> Option<ResourcePressure> {
+    let content = fs::read_to_string(path).ok()?;
+    let mut some = None;
+    let mut full = None;
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let kind = fields.next();
+        let mut parsed = PressureLine::default();
+        for field in fields {
+            match field.split_once('=') {
+                Some(("avg10", value)) => parsed.avg10 = value.parse().unwrap_or(0.0),
+                Some(("avg60", value)) => parsed.avg60 = value.parse().unwrap_or(0.0),
+                Some(("avg300", value)) => parsed.avg300 = value.parse().unwrap_or(0.0),
+                Some(("total", value)) => parsed.total_usec = value.parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+        match kind {
+            Some("some") => some = Some(parsed),
+            Some("full") => full = Some(parsed),
+            _ => {}
+        }
+    }
+    Some(ResourcePressure { some: some?, full })
+}
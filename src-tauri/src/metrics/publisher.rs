@@ -0,0 +1,167 @@
+use crate::database::service::{DiskHistoryRecord, MemoryHistoryRecord, NetworkHistoryRecord};
+use crate::error::{DashError, DashResult};
+use crate::metrics::cpu;
+use crate::metrics::sampler::{Sample, Sampler};
+use crate::types::{ApiResponse, CoreFrequency, CpuStats};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const TICK_EVENT: &str = "metrics://tick";
+pub const MIN_INTERVAL_MS: u64 = 250;
+pub const MAX_INTERVAL_MS: u64 = 60_000;
+
+// 没有订阅者时的轮询间隔
+const IDLE_POLL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsTick {
+    pub subscription_id: u64,
+    pub cpu: CpuStats,
+    pub memory: MemorySnapshot,
+    pub networks: Vec<NetworkHistoryRecord>,
+    pub disks: Vec<DiskHistoryRecord>,
+}
+
+// 在历史记录的字段之外带上总量与空闲量，前端不必用 used + available 推算
+#[derive(Debug, Clone, Serialize)]
+pub struct MemorySnapshot {
+    #[serde(flatten)]
+    pub record: MemoryHistoryRecord,
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+}
+
+pub type TickChannel = Channel<ApiResponse<MetricsTick>>;
+
+// 每个订阅者通过自己的 Channel 接收数据，页面刷新后发送失败即自动退订；
+// 同一份数据也以 metrics://tick 事件广播，监听方按 subscription_id 区分
+struct Subscription {
+    interval: Duration,
+    next_due: Instant,
+    channel: TickChannel,
+}
+
+#[derive(Default)]
+pub struct PublisherState {
+    subscriptions: Mutex<HashMap<u64, Subscription>>,
+    next_id: AtomicU64,
+}
+
+impl PublisherState {
+    pub fn subscribe(&self, interval_ms: u64, channel: TickChannel) -> DashResult<u64> {
+        let interval = validate_interval(interval_ms)?;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        self.subscriptions.lock().unwrap().insert(
+            id,
+            Subscription {
+                interval,
+                next_due: Instant::now(),
+                channel,
+            },
+        );
+        Ok(id)
+    }
+
+    pub fn update(&self, subscription_id: u64, interval_ms: u64) -> DashResult<()> {
+        let interval = validate_interval(interval_ms)?;
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let subscription = subscriptions
+            .get_mut(&subscription_id)
+            .ok_or_else(|| DashError::NotFound(format!("subscription {} not found", subscription_id)))?;
+        subscription.interval = interval;
+        subscription.next_due = Instant::now();
+        Ok(())
+    }
+
+    pub fn unsubscribe(&self, subscription_id: u64) -> bool {
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .remove(&subscription_id)
+            .is_some()
+    }
+
+    // 返回当前到期的订阅并推进其下一次触发时间，以及距离最近一次到期的等待时长
+    fn take_due(&self, now: Instant) -> (Vec<(u64, TickChannel)>, Duration) {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let mut due = Vec::new();
+        let mut wait = IDLE_POLL;
+        for (id, subscription) in subscriptions.iter_mut() {
+            if subscription.next_due <= now {
+                due.push((*id, subscription.channel.clone()));
+                subscription.next_due += subscription.interval;
+                if subscription.next_due <= now {
+                    subscription.next_due = now + subscription.interval;
+                }
+            }
+            wait = wait.min(subscription.next_due.saturating_duration_since(now));
+        }
+        (due, wait)
+    }
+}
+
+fn validate_interval(interval_ms: u64) -> DashResult<Duration> {
+    if !(MIN_INTERVAL_MS..=MAX_INTERVAL_MS).contains(&interval_ms) {
+        return Err(DashError::InvalidArgument(format!(
+            "interval_ms must be between {} and {}",
+            MIN_INTERVAL_MS, MAX_INTERVAL_MS
+        )));
+    }
+    Ok(Duration::from_millis(interval_ms))
+}
+
+pub fn cpu_stats(sample: &Sample, frequencies: Vec<CoreFrequency>) -> CpuStats {
+    cpu::cpu_stats(sample.cpu.usage, sample.cpu.frequency, sample.cpu.per_core.clone(), frequencies)
+}
+
+pub fn spawn(app: AppHandle) {
+    thread::Builder::new()
+        .name("metrics-publisher".to_string())
+        .spawn(move || run(app))
+        .expect("Failed to spawn metrics publisher");
+}
+
+fn run(app: AppHandle) {
+    let mut sampler = Sampler::new();
+    let mut frequencies = cpu::FrequencyCache::default();
+
+    loop {
+        let state = app.state::<PublisherState>();
+        let (due, wait) = state.take_due(Instant::now());
+
+        if !due.is_empty() {
+            let sample = sampler.sample();
+            let cpu = cpu_stats(&sample, frequencies.get());
+            let timestamp = chrono::Utc::now().timestamp_millis() as u64;
+            for (subscription_id, channel) in due {
+                let payload = ApiResponse {
+                    data: MetricsTick {
+                        subscription_id,
+                        cpu: cpu.clone(),
+                        memory: MemorySnapshot {
+                            record: sample.memory.clone(),
+                            total_bytes: sample.memory_total,
+                            free_bytes: sample.memory_free,
+                        },
+                        networks: sample.networks.clone(),
+                        disks: sample.disks.clone(),
+                    },
+                    timestamp,
+                };
+                let _ = app.emit(TICK_EVENT, &payload);
+                if let Err(e) = channel.send(payload) {
+                    eprintln!("metrics publisher: dropping subscription {}: {}", subscription_id, e);
+                    state.unsubscribe(subscription_id);
+                }
+            }
+        }
+
+        thread::sleep(wait.max(Duration::from_millis(10)));
+    }
+}
@@ -0,0 +1,222 @@
+use crate::database::service::{
+    CpuCoreRecord, CpuHistoryRecord, DiskHistoryRecord, MemoryHistoryRecord, NetworkHistoryRecord,
+    ProcessHistoryRecord,
+};
+use crate::metrics::cpu::{self, CpuStatSampler};
+use crate::metrics::memory::{self, ZramCache};
+use crate::types::ProcessInfo;
+use std::collections::HashMap;
+use std::time::Instant;
+use sysinfo::{Disks, Networks, System};
+
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub timestamp: i64,
+    pub cpu: CpuHistoryRecord,
+    // 来自 /proc/stat，非 Linux 以及第一次采样时为空
+    pub cores: Vec<CpuCoreRecord>,
+    pub memory: MemoryHistoryRecord,
+    // 历史表不记录总量与空闲量，只随实时推送发给前端
+    pub memory_total: u64,
+    pub memory_free: u64,
+    pub networks: Vec<NetworkHistoryRecord>,
+    pub disks: Vec<DiskHistoryRecord>,
+}
+
+// 持有 sysinfo 句柄与上一次的计数器，速率按两次采样之间的差值计算
+pub struct Sampler {
+    system: System,
+    networks: Networks,
+    disks: Disks,
+    cpu_stat: CpuStatSampler,
+    zram: ZramCache,
+    disk_io: HashMap<String, (u64, u64)>,
+    last_refresh: Instant,
+}
+
+impl Sampler {
+    pub fn new() -> Self {
+        let mut system = System::new();
+        system.refresh_cpu();
+        system.refresh_memory();
+        Self {
+            system,
+            networks: Networks::new_with_refreshed_list(),
+            disks: Disks::new_with_refreshed_list(),
+            cpu_stat: CpuStatSampler::new(),
+            zram: ZramCache::default(),
+            disk_io: read_disk_io(),
+            last_refresh: Instant::now(),
+        }
+    }
+
+    pub fn sample(&mut self) -> Sample {
+        let elapsed = self.last_refresh.elapsed().as_secs_f64().max(0.001);
+        self.last_refresh = Instant::now();
+        let timestamp = chrono::Utc::now().timestamp();
+
+        self.system.refresh_cpu();
+        self.system.refresh_memory();
+        self.networks.refresh();
+        self.disks.refresh_list();
+
+        let cpus = self.system.cpus();
+        let cpu_stat = self.cpu_stat.sample();
+        let cpu = CpuHistoryRecord {
+            timestamp,
+            usage: self.system.global_cpu_info().cpu_usage(),
+            frequency: cpus.first().map(|c| c.frequency()).unwrap_or(0),
+            per_core: cpus.iter().map(|c| c.cpu_usage()).collect(),
+            times: cpu_stat.as_ref().map(|stat| stat.total),
+            context_switches_per_sec: cpu_stat.as_ref().map(|stat| stat.context_switches_per_sec),
+            interrupts_per_sec: cpu_stat.as_ref().map(|stat| stat.interrupts_per_sec),
+            forks_per_sec: cpu_stat.as_ref().map(|stat| stat.forks_per_sec),
+            load_avg: cpu::load_average().map(|load| load.as_array()),
+        };
+        // 核心使用率与 /proc/stat 的时间占比取自同一区间，两者口径一致
+        let cores = cpu_stat
+            .map(|stat| {
+                stat.cores
+                    .into_iter()
+                    .map(|(core, times)| CpuCoreRecord {
+                        timestamp,
+                        core,
+                        usage: (100.0 - times.idle - times.iowait).max(0.0),
+                        times,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let total_memory = self.system.total_memory();
+        let used_memory = self.system.used_memory();
+        let memory = MemoryHistoryRecord {
+            timestamp,
+            usage_percent: percent(used_memory, total_memory),
+            used_bytes: used_memory,
+            available_bytes: self.system.available_memory(),
+            swap_used_bytes: self.system.used_swap(),
+            swap_total_bytes: self.system.total_swap(),
+            breakdown: memory::read_breakdown(&mut self.zram).ok(),
+        };
+
+        let mut networks: Vec<NetworkHistoryRecord> = self
+            .networks
+            .list()
+            .iter()
+            .filter(|(name, _)| name.as_str() != "lo")
+            .map(|(name, data)| NetworkHistoryRecord {
+                timestamp,
+                interface_name: name.clone(),
+                rx_bytes: data.total_received(),
+                tx_bytes: data.total_transmitted(),
+                rx_speed: data.received() as f64 / elapsed,
+                tx_speed: data.transmitted() as f64 / elapsed,
+            })
+            .collect();
+        networks.sort_by(|a, b| a.interface_name.cmp(&b.interface_name));
+
+        let disk_io = read_disk_io();
+        let mut disks: Vec<DiskHistoryRecord> = Vec::new();
+        for disk in self.disks.list() {
+            let name = disk.name().to_string_lossy();
+            let name = name.trim_start_matches("/dev/").to_string();
+            // 同一设备可能挂载在多个位置，只记录一次
+            if disks.iter().any(|d| d.disk_name == name) {
+                continue;
+            }
+            let (read_speed, write_speed) = match (disk_io.get(&name), self.disk_io.get(&name)) {
+                (Some(&(read, written)), Some(&(prev_read, prev_written))) => (
+                    read.saturating_sub(prev_read) as f64 / elapsed,
+                    written.saturating_sub(prev_written) as f64 / elapsed,
+                ),
+                _ => (0.0, 0.0),
+            };
+            let total = disk.total_space();
+            disks.push(DiskHistoryRecord {
+                timestamp,
+                disk_name: name,
+                read_speed,
+                write_speed,
+                usage_percent: percent(total.saturating_sub(disk.available_space()), total),
+            });
+        }
+        self.disk_io = disk_io;
+
+        Sample {
+            timestamp,
+            cpu,
+            cores,
+            memory,
+            memory_total: total_memory,
+            memory_free: self.system.free_memory(),
+            networks,
+            disks,
+        }
+    }
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// CPU 前 N 名与内存前 N 名的并集，同一进程只记录一次；占用为 0 的不算入前 N 名
+pub fn top_processes(processes: &[ProcessInfo], top_n: usize, timestamp: i64) -> Vec<ProcessHistoryRecord> {
+    let mut processes: Vec<&ProcessInfo> = processes.iter().collect();
+    processes.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+    let mut pids: Vec<u32> = processes
+        .iter()
+        .take_while(|p| p.cpu_usage > 0.0)
+        .take(top_n)
+        .map(|p| p.pid)
+        .collect();
+    processes.sort_by_key(|p| std::cmp::Reverse(p.memory));
+    pids.extend(processes.iter().take_while(|p| p.memory > 0).take(top_n).map(|p| p.pid));
+
+    processes
+        .into_iter()
+        .filter(|p| pids.contains(&p.pid))
+        .map(|p| ProcessHistoryRecord {
+            timestamp,
+            pid: p.pid,
+            name: p.name.clone(),
+            command: p.command.clone(),
+            user: p.user.clone(),
+            cpu_usage: p.cpu_usage,
+            memory_bytes: p.memory,
+        })
+        .collect()
+}
+
+fn percent(used: u64, total: u64) -> f32 {
+    if total == 0 {
+        0.0
+    } else {
+        (used as f64 / total as f64 * 100.0) as f32
+    }
+}
+
+// 块设备累计读写字节数 (/proc/diskstats 中扇区固定为 512 字节)
+#[cfg(target_os = "linux")]
+fn read_disk_io() -> HashMap<String, (u64, u64)> {
+    let content = std::fs::read_to_string("/proc/diskstats").unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                return None;
+            }
+            let sectors_read: u64 = fields[5].parse().ok()?;
+            let sectors_written: u64 = fields[9].parse().ok()?;
+            Some((fields[2].to_string(), (sectors_read * 512, sectors_written * 512)))
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_disk_io() -> HashMap<String, (u64, u64)> {
+    HashMap::new()
+}
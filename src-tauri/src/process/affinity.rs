@@ -0,0 +1,233 @@
+use crate::error::{DashError, DashResult};
+use serde::{Deserialize, Serialize};
+#[cfg(target_os = "linux")]
+use std::fs;
+
+// 内核列出的在线 CPU，离线或不存在的编号不在其中
+#[cfg(target_os = "linux")]
+const CPU_ONLINE_PATH: &str = "/sys/devices/system/cpu/online";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadAffinity {
+    pub tid: u32,
+    pub cpus: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AffinityInfo {
+    pub pid: u32,
+    pub core_count: usize,
+    // 主线程的亲和性，以 CPU 编号列出
+    pub cpus: Vec<usize>,
+    // 只有请求了 all_threads 时才返回各线程的亲和性
+    pub threads: Option<Vec<ThreadAffinity>>,
+}
+
+// 与 CpuStats::core_count 同源：sysinfo 报告的逻辑 CPU 数
+pub fn core_count() -> usize {
+    let mut system = sysinfo::System::new();
+    system.refresh_cpu();
+    system.cpus().len()
+}
+
+// 格式："0-3,8-11"；CPU 编号可能不连续（中间的核心离线），不能用核心数判断
+#[cfg(target_os = "linux")]
+fn parse_cpu_list(content: &str) -> Option<Vec<usize>> {
+    let mut cpus = Vec::new();
+    for part in content.trim().split(',').filter(|part| !part.is_empty()) {
+        match part.split_once('-') {
+            Some((first, last)) => {
+                let (first, last): (usize, usize) = (first.parse().ok()?, last.parse().ok()?);
+                if first > last {
+                    return None;
+                }
+                cpus.extend(first..=last);
+            }
+            None => cpus.push(part.parse().ok()?),
+        }
+    }
+    Some(cpus)
+}
+
+// 优先读取 sysfs 的在线列表，读不到时退回本进程允许使用的 CPU
+#[cfg(target_os = "linux")]
+fn available_cpus() -> DashResult<Vec<usize>> {
+    if let Some(cpus) = fs::read_to_string(CPU_ONLINE_PATH)
+        .ok()
+        .and_then(|content| parse_cpu_list(&content))
+        .filter(|cpus| !cpus.is_empty())
+    {
+        return Ok(cpus);
+    }
+    let pid = std::process::id();
+    read_mask(pid, pid)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn available_cpus() -> DashResult<Vec<usize>> {
+    Ok((0..core_count()).collect())
+}
+
+// 去重排序，并确认每个编号都是当前可用的 CPU
+fn validate(cpus: &[usize], available: &[usize]) -> DashResult<Vec<usize>> {
+    if cpus.is_empty() {
+        return Err(DashError::InvalidArgument("at least one CPU must be selected".to_string()));
+    }
+    if let Some(cpu) = cpus.iter().find(|cpu| !available.contains(cpu)) {
+        return Err(DashError::InvalidArgument(format!(
+            "CPU {} does not exist or is offline, available CPUs: {:?}",
+            cpu, available
+        )));
+    }
+    let mut cpus = cpus.to_vec();
+    cpus.sort_unstable();
+    cpus.dedup();
+    Ok(cpus)
+}
+
+#[cfg(target_os = "linux")]
+pub fn get(pid: u32, all_threads: bool) -> DashResult<AffinityInfo> {
+    crate::process::signal::validate_pid(pid)?;
+    let core_count = core_count();
+    let cpus = read_mask(pid, pid)?;
+    let threads = if all_threads {
+        let mut threads = Vec::new();
+        for tid in tasks_of(pid)? {
+            // 线程可能在遍历过程中退出
+            if let Ok(cpus) = read_mask(pid, tid) {
+                threads.push(ThreadAffinity { tid, cpus });
+            }
+        }
+        Some(threads)
+    } else {
+        None
+    };
+    Ok(AffinityInfo {
+        pid,
+        core_count,
+        cpus,
+        threads,
+    })
+}
+
+// 亲和性按线程生效：只改主线程时，已经存在的其他线程保持原来的设置
+#[cfg(target_os = "linux")]
+pub fn set(pid: u32, cpus: &[usize], all_threads: bool) -> DashResult<AffinityInfo> {
+    crate::process::signal::validate_pid(pid)?;
+    let cpus = validate(cpus, &available_cpus()?)?;
+    // CPU_SET 不检查越界，超出 cpu_set_t 容量的编号必须先拦下
+    let max = libc::CPU_SETSIZE as usize;
+    if let Some(cpu) = cpus.iter().find(|cpu| **cpu >= max) {
+        return Err(DashError::InvalidArgument(format!(
+            "CPU {} exceeds the {} CPUs supported by sched_setaffinity",
+            cpu, max
+        )));
+    }
+
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for cpu in &cpus {
+        unsafe { libc::CPU_SET(*cpu, &mut set) };
+    }
+
+    let targets = if all_threads { tasks_of(pid)? } else { vec![pid] };
+    // 逐个线程设置前先记下原来的掩码，中途失败时把已经改过的线程恢复原样，不留下一半线程被绑定的状态
+    let mut applied: Vec<(u32, libc::cpu_set_t)> = Vec::with_capacity(targets.len());
+    for tid in targets {
+        let result = get_set(tid).and_then(|original| put_set(tid, &set).map(|_| original));
+        match result {
+            Ok(original) => applied.push((tid, original)),
+            // 线程在遍历过程中退出
+            Err(err) if err.raw_os_error() == Some(libc::ESRCH) && tid != pid => continue,
+            Err(err) => {
+                for (tid, original) in applied.iter().rev() {
+                    if let Err(e) = put_set(*tid, original) {
+                        eprintln!("affinity: failed to restore the CPU affinity of thread {}: {}", tid, e);
+                    }
+                }
+                if err.raw_os_error() == Some(libc::EINVAL) {
+                    return Err(DashError::InvalidArgument(format!(
+                        "CPUs {:?} are not allowed for process {} (restricted by its cpuset)",
+                        cpus, pid
+                    )));
+                }
+                return Err(crate::process::os_error(err, pid, "set the CPU affinity of"));
+            }
+        }
+    }
+    get(pid, all_threads)
+}
+
+#[cfg(target_os = "linux")]
+fn get_set(tid: u32) -> std::io::Result<libc::cpu_set_t> {
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    let result = unsafe {
+        libc::sched_getaffinity(tid as libc::pid_t, std::mem::size_of::<libc::cpu_set_t>(), &mut set)
+    };
+    if result != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(set)
+}
+
+#[cfg(target_os = "linux")]
+fn put_set(tid: u32, set: &libc::cpu_set_t) -> std::io::Result<()> {
+    let result = unsafe {
+        libc::sched_setaffinity(tid as libc::pid_t, std::mem::size_of::<libc::cpu_set_t>(), set)
+    };
+    if result != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn read_mask(pid: u32, tid: u32) -> DashResult<Vec<usize>> {
+    let set = get_set(tid).map_err(|err| crate::process::os_error(err, pid, "read the CPU affinity of"))?;
+    let max = libc::CPU_SETSIZE as usize;
+    Ok((0..max).filter(|cpu| unsafe { libc::CPU_ISSET(*cpu, &set) }).collect())
+}
+
+#[cfg(target_os = "linux")]
+fn tasks_of(pid: u32) -> DashResult<Vec<u32>> {
+    crate::process::procfs::list_tasks(pid)
+        .map_err(|_| DashError::NotFound(format!("process {} not found", pid)))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn get(_pid: u32, _all_threads: bool) -> DashResult<AffinityInfo> {
+    Err(unsupported())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set(_pid: u32, cpus: &[usize], _all_threads: bool) -> DashResult<AffinityInfo> {
+    validate(cpus, &available_cpus()?)?;
+    Err(unsupported())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unsupported() -> DashError {
+    DashError::Unsupported("CPU affinity is only supported on Linux".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn parse_cpu_list_ranges() {
+        assert_eq!(parse_cpu_list("0-3,8-11\n"), Some(vec![0, 1, 2, 3, 8, 9, 10, 11]));
+        assert_eq!(parse_cpu_list("0"), Some(vec![0]));
+        assert_eq!(parse_cpu_list("0,2,4-5"), Some(vec![0, 2, 4, 5]));
+        assert_eq!(parse_cpu_list("3-1"), None);
+        assert_eq!(parse_cpu_list("a-b"), None);
+    }
+
+    #[test]
+    fn validate_rejects_offline_cpus() {
+        let available = [0, 1, 2, 3, 8, 9];
+        assert_eq!(validate(&[9, 0, 0], &available).unwrap(), vec![0, 9]);
+        assert!(validate(&[4], &available).is_err());
+        assert!(validate(&[], &available).is_err());
+    }
+}
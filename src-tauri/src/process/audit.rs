@@ -0,0 +1,130 @@
+use crate::database::service::ProcessActionRecord;
+use crate::database::DatabaseService;
+use crate::error::{DashError, DashResult};
+use crate::process::signal::Signal;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_QUERY_LIMIT: u32 = 500;
+pub const MAX_QUERY_LIMIT: u32 = 5000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionOutcome {
+    Success,
+    Failed,
+    Refused,
+}
+
+impl ActionOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ActionOutcome::Success => "success",
+            ActionOutcome::Failed => "failed",
+            ActionOutcome::Refused => "refused",
+        }
+    }
+
+    fn of<T>(result: &DashResult<T>) -> Self {
+        match result {
+            Ok(_) => ActionOutcome::Success,
+            Err(DashError::Protected { .. }) => ActionOutcome::Refused,
+            Err(_) => ActionOutcome::Failed,
+        }
+    }
+}
+
+// 操作前先记下目标进程的名字和命令行，操作完成后进程可能已经不存在
+pub struct PendingAction {
+    timestamp: i64,
+    action: &'static str,
+    pid: u32,
+    signal: Option<Signal>,
+    forced: bool,
+    process_name: Option<String>,
+    command_line: Option<String>,
+}
+
+impl PendingAction {
+    pub fn begin(action: &'static str, pid: u32, signal: Option<Signal>, forced: bool) -> Self {
+        let (process_name, command_line) = describe(pid);
+        Self {
+            timestamp: chrono::Utc::now().timestamp(),
+            action,
+            pid,
+            signal,
+            forced,
+            process_name,
+            command_line,
+        }
+    }
+
+    // 成功时把返回值（终止步骤、进程树报告等）序列化进 detail；
+    // 写审计日志失败只打印，不影响操作本身的结果
+    pub fn finish<T: Serialize>(self, db: &DatabaseService, result: &DashResult<T>) {
+        let record = ProcessActionRecord {
+            id: 0,
+            timestamp: self.timestamp,
+            actor: actor(),
+            action: self.action.to_string(),
+            signal: self.signal.map(|signal| signal.name().to_string()),
+            pid: self.pid,
+            process_name: self.process_name,
+            command_line: self.command_line,
+            forced: self.forced,
+            outcome: ActionOutcome::of(result).as_str().to_string(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+            detail: result
+                .as_ref()
+                .ok()
+                .and_then(|value| serde_json::to_string(value).ok())
+                .filter(|json| json != "null"),
+        };
+        if let Err(e) = db.insert_process_action(&record) {
+            eprintln!("process audit: failed to record {} on {}: {}", record.action, record.pid, e);
+        }
+    }
+}
+
+// 操作由运行 dashsys 的系统用户发起
+fn actor() -> String {
+    #[cfg(target_os = "linux")]
+    {
+        let uid = unsafe { libc::getuid() };
+        if let Some(name) = crate::process::procfs::user_names().remove(&uid) {
+            return name;
+        }
+    }
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn describe(pid: u32) -> (Option<String>, Option<String>) {
+    use crate::process::procfs;
+
+    let name = procfs::read_status(pid)
+        .ok()
+        .map(|status| status.name)
+        .filter(|name| !name.is_empty());
+    (name, procfs::read_cmdline(pid))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn describe(pid: u32) -> (Option<String>, Option<String>) {
+    let mut system = sysinfo::System::new();
+    let pid = sysinfo::Pid::from_u32(pid);
+    if !system.refresh_process(pid) {
+        return (None, None);
+    }
+    match system.process(pid) {
+        Some(process) => {
+            let command = process.cmd().join(" ");
+            (
+                Some(process.name().to_string()),
+                if command.is_empty() { None } else { Some(command) },
+            )
+        }
+        None => (None, None),
+    }
+}
@@ -0,0 +1,33 @@
+pub mod affinity;
+pub mod audit;
+pub mod monitor;
+pub mod priority;
+pub mod safety;
+pub mod signal;
+pub mod suspend;
+pub mod watch;
+#[cfg(target_os = "linux")]
+pub mod procfs;
+#[cfg(target_os = "linux")]
+pub mod tree;
+
+#[cfg(unix)]
+use crate::error::DashError;
+
+// 把刚失败的系统调用的 errno 转成对应的错误，action 形如 "send SIGTERM to"。
+// action 需要 format! 时先取 errno 再拼接，改用 os_error，避免分配内存时 errno 被覆盖
+#[cfg(unix)]
+pub(crate) fn last_os_error(pid: u32, action: &str) -> DashError {
+    os_error(std::io::Error::last_os_error(), pid, action)
+}
+
+#[cfg(unix)]
+pub(crate) fn os_error(err: std::io::Error, pid: u32, action: &str) -> DashError {
+    match err.raw_os_error() {
+        Some(libc::ESRCH) => DashError::NotFound(format!("process {} not found", pid)),
+        Some(libc::EPERM) | Some(libc::EACCES) => {
+            DashError::PermissionDenied(format!("not permitted to {} process {}", action, pid))
+        }
+        _ => DashError::Io(err),
+    }
+}
@@ -0,0 +1,165 @@
+use crate::error::DashResult;
+use crate::types::{ProcessDetails, ProcessInfo};
+#[cfg(target_os = "linux")]
+use crate::types::ThreadInfo;
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::sync::Mutex;
+#[cfg(target_os = "linux")]
+use std::time::Instant;
+
+// 上一次列举时每个进程累计的 CPU 滴答数，按 (pid, starttime) 区分以免 PID 复用后算错
+#[cfg(target_os = "linux")]
+struct CpuSnapshot {
+    taken: Instant,
+    ticks: HashMap<(u32, u64), u64>,
+}
+
+// 进程列表的 CPU% 是两次调用之间的差值，第一次调用时全部为 0
+#[derive(Default)]
+pub struct ProcessMonitor {
+    #[cfg(target_os = "linux")]
+    previous: Mutex<Option<CpuSnapshot>>,
+}
+
+impl ProcessMonitor {
+    #[cfg(target_os = "linux")]
+    pub fn list(&self) -> DashResult<Vec<ProcessInfo>> {
+        use crate::process::procfs;
+
+        let pids = procfs::list_pids()?;
+        let boot_time = procfs::boot_time()?;
+        let clock_ticks = procfs::clock_ticks();
+        let users = procfs::user_names();
+        let now = Instant::now();
+
+        let mut previous = self.previous.lock().unwrap();
+        let elapsed = previous
+            .as_ref()
+            .map(|snapshot| now.duration_since(snapshot.taken).as_secs_f64())
+            .filter(|secs| *secs > 0.0);
+        let mut ticks = HashMap::with_capacity(pids.len());
+        let mut processes = Vec::with_capacity(pids.len());
+
+        for pid in pids {
+            // 进程可能在列举过程中退出，读不到就跳过
+            let Ok(stat) = procfs::read_stat(pid) else {
+                continue;
+            };
+            let cpu_usage = match (previous.as_ref(), elapsed) {
+                (Some(snapshot), Some(secs)) => snapshot.usage(&stat, clock_ticks, secs),
+                _ => 0.0,
+            };
+            ticks.insert((pid, stat.starttime), stat.cpu_ticks());
+            processes.push(process_info(stat, cpu_usage, &users, boot_time, clock_ticks));
+        }
+
+        *previous = Some(CpuSnapshot { taken: now, ticks });
+        Ok(processes)
+    }
+
+    // CPU% 沿用上一次列举的快照计算，不更新快照，以免打乱列表的采样间隔
+    #[cfg(target_os = "linux")]
+    pub fn details(&self, pid: u32) -> DashResult<ProcessDetails> {
+        use crate::error::DashError;
+        use crate::process::procfs;
+
+        let stat = procfs::read_stat(pid).map_err(|_| DashError::NotFound(format!("process {} not found", pid)))?;
+        let clock_ticks = procfs::clock_ticks();
+        let cpu_usage = self
+            .previous
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|snapshot| {
+                let secs = snapshot.taken.elapsed().as_secs_f64();
+                if secs > 0.0 { snapshot.usage(&stat, clock_ticks, secs) } else { 0.0 }
+            })
+            .unwrap_or(0.0);
+
+        let thread_list = procfs::list_tasks(pid)
+            .unwrap_or_default()
+            .into_iter()
+            // 线程可能在遍历过程中退出
+            .filter_map(|tid| procfs::read_task_stat(pid, tid).ok())
+            .map(|task| ThreadInfo {
+                tid: task.pid,
+                status: procfs::state_name(task.state).to_string(),
+                cpu_time: task.cpu_ticks() as f64 / clock_ticks as f64,
+                nice: task.nice,
+                name: task.comm,
+            })
+            .collect();
+
+        Ok(ProcessDetails {
+            info: process_info(stat, cpu_usage, &procfs::user_names(), procfs::boot_time()?, clock_ticks),
+            executable: procfs::read_link(pid, "exe"),
+            working_directory: procfs::read_link(pid, "cwd"),
+            environment: procfs::read_environ(pid).ok(),
+            open_files: procfs::read_fds(pid).ok(),
+            memory_maps: procfs::read_smaps_rollup(pid).ok(),
+            limits: procfs::read_limits(pid).ok(),
+            cgroup: procfs::read_cgroup(pid),
+            thread_list,
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn list(&self) -> DashResult<Vec<ProcessInfo>> {
+        Err(crate::error::DashError::Unsupported(
+            "Process listing is only implemented on Linux".to_string(),
+        ))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn details(&self, _pid: u32) -> DashResult<ProcessDetails> {
+        Err(crate::error::DashError::Unsupported(
+            "Process details are only implemented on Linux".to_string(),
+        ))
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl CpuSnapshot {
+    fn usage(&self, stat: &crate::process::procfs::ProcStat, clock_ticks: u64, secs: f64) -> f32 {
+        self.ticks
+            .get(&(stat.pid, stat.starttime))
+            .map(|before| {
+                let delta = stat.cpu_ticks().saturating_sub(*before) as f64;
+                (delta / clock_ticks as f64 / secs * 100.0) as f32
+            })
+            .unwrap_or(0.0)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn process_info(
+    stat: crate::process::procfs::ProcStat,
+    cpu_usage: f32,
+    users: &HashMap<u32, String>,
+    boot_time: u64,
+    clock_ticks: u64,
+) -> ProcessInfo {
+    use crate::process::procfs;
+
+    let pid = stat.pid;
+    let status = procfs::read_status(pid).unwrap_or_default();
+    let (disk_read, disk_written) = procfs::read_io(pid).unwrap_or((0, 0));
+    ProcessInfo {
+        pid,
+        name: if status.name.is_empty() { stat.comm } else { status.name },
+        status: procfs::state_name(stat.state).to_string(),
+        cpu_usage,
+        memory: status.vm_rss,
+        virtual_memory: status.vm_size,
+        disk_read,
+        disk_written,
+        parent: if stat.ppid == 0 { None } else { Some(stat.ppid) },
+        command: procfs::read_cmdline(pid),
+        user: status.uid.and_then(|uid| users.get(&uid).cloned()),
+        uid: status.uid,
+        start_time: boot_time + stat.starttime / clock_ticks,
+        threads: stat.num_threads,
+    }
+}
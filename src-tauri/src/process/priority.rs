@@ -0,0 +1,170 @@
+use crate::error::{DashError, DashResult};
+use serde::{Deserialize, Serialize};
+
+pub const MIN_NICE: i32 = -20;
+pub const MAX_NICE: i32 = 19;
+pub const MAX_IO_LEVEL: u8 = 7;
+
+#[cfg(target_os = "linux")]
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+#[cfg(target_os = "linux")]
+const IOPRIO_CLASS_SHIFT: u32 = 13;
+
+// I/O 调度类别，对应内核的 IOPRIO_CLASS_*
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IoClass {
+    None,
+    Realtime,
+    BestEffort,
+    Idle,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriorityInfo {
+    pub pid: u32,
+    pub nice: i32,
+    pub io_class: IoClass,
+    // idle 类别没有级别；none 类别按 nice 推算
+    pub io_level: Option<u8>,
+}
+
+#[cfg(target_os = "linux")]
+impl IoClass {
+    fn raw(self) -> u32 {
+        match self {
+            IoClass::None => 0,
+            IoClass::Realtime => 1,
+            IoClass::BestEffort => 2,
+            IoClass::Idle => 3,
+        }
+    }
+
+    fn from_raw(raw: u32) -> Self {
+        match raw {
+            1 => IoClass::Realtime,
+            2 => IoClass::BestEffort,
+            3 => IoClass::Idle,
+            _ => IoClass::None,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn get(pid: u32) -> DashResult<PriorityInfo> {
+    use crate::process::procfs;
+
+    let stat = procfs::read_stat(pid)
+        .map_err(|_| DashError::NotFound(format!("process {} not found", pid)))?;
+    let raw = unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, pid as libc::c_int) };
+    if raw < 0 {
+        return Err(crate::process::last_os_error(pid, "read the I/O priority of"));
+    }
+    let raw = raw as u32;
+    let io_class = IoClass::from_raw(raw >> IOPRIO_CLASS_SHIFT);
+    let io_level = match io_class {
+        IoClass::Idle => None,
+        // 未显式设置时内核按 (nice + 20) / 5 换算 best-effort 级别
+        IoClass::None => Some(((stat.nice + 20) / 5).clamp(0, MAX_IO_LEVEL as i32) as u8),
+        _ => Some((raw & ((1 << IOPRIO_CLASS_SHIFT) - 1)) as u8),
+    };
+    Ok(PriorityInfo {
+        pid,
+        nice: stat.nice,
+        io_class,
+        io_level,
+    })
+}
+
+// Linux 上 nice 值是按线程的，setpriority(PRIO_PROCESS, pid) 只改主线程，需要逐个线程设置
+#[cfg(target_os = "linux")]
+pub fn set_nice(pid: u32, nice: i32) -> DashResult<PriorityInfo> {
+    if !(MIN_NICE..=MAX_NICE).contains(&nice) {
+        return Err(DashError::InvalidArgument(format!(
+            "nice must be between {} and {}",
+            MIN_NICE, MAX_NICE
+        )));
+    }
+    for tid in threads_of(pid)? {
+        if unsafe { libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, nice) } != 0 {
+            let err = std::io::Error::last_os_error();
+            let err = crate::process::os_error(err, pid, &format!("set nice {} on", nice));
+            // 线程在遍历过程中退出不算失败
+            if matches!(err, DashError::NotFound(_)) && tid != pid {
+                continue;
+            }
+            return Err(match err {
+                DashError::PermissionDenied(message) => DashError::PermissionDenied(format!(
+                    "{} (raising priority or changing another user's process requires CAP_SYS_NICE)",
+                    message
+                )),
+                other => other,
+            });
+        }
+    }
+    get(pid)
+}
+
+#[cfg(target_os = "linux")]
+pub fn set_io_priority(pid: u32, class: IoClass, level: Option<u8>) -> DashResult<PriorityInfo> {
+    let data = match (class, level) {
+        (IoClass::None | IoClass::Idle, _) => 0,
+        (_, Some(level)) if level <= MAX_IO_LEVEL => level as u32,
+        (_, Some(_)) => {
+            return Err(DashError::InvalidArgument(format!(
+                "io level must be between 0 and {}",
+                MAX_IO_LEVEL
+            )))
+        }
+        // 未指定级别时使用内核默认的 4
+        (_, None) => 4,
+    };
+    let value = (class.raw() << IOPRIO_CLASS_SHIFT) | data;
+
+    for tid in threads_of(pid)? {
+        let result = unsafe {
+            libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, tid as libc::c_int, value as libc::c_int)
+        };
+        if result != 0 {
+            let err = crate::process::last_os_error(pid, "set the I/O priority of");
+            if matches!(err, DashError::NotFound(_)) && tid != pid {
+                continue;
+            }
+            return Err(match err {
+                DashError::PermissionDenied(message) => DashError::PermissionDenied(format!(
+                    "{} (the realtime class requires CAP_SYS_ADMIN)",
+                    message
+                )),
+                other => other,
+            });
+        }
+    }
+    get(pid)
+}
+
+#[cfg(target_os = "linux")]
+fn threads_of(pid: u32) -> DashResult<Vec<u32>> {
+    crate::process::signal::validate_pid(pid)?;
+    crate::process::procfs::list_tasks(pid)
+        .map_err(|_| DashError::NotFound(format!("process {} not found", pid)))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn get(_pid: u32) -> DashResult<PriorityInfo> {
+    Err(unsupported())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_nice(_pid: u32, _nice: i32) -> DashResult<PriorityInfo> {
+    Err(unsupported())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_io_priority(_pid: u32, _class: IoClass, _level: Option<u8>) -> DashResult<PriorityInfo> {
+    Err(unsupported())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unsupported() -> DashError {
+    DashError::Unsupported("Process priority control is only implemented on Linux".to_string())
+}
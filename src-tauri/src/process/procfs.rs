@@ -0,0 +1,326 @@
+use crate::types::{MemoryMapsSummary, OpenFile, ResourceLimit};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+
+// 内核线程在 /proc/<pid>/stat 的 flags 中带有 PF_KTHREAD
+pub const PF_KTHREAD: u32 = 0x0020_0000;
+
+// /proc/<pid>/stat 中用到的字段
+#[derive(Debug, Clone)]
+pub struct ProcStat {
+    pub pid: u32,
+    pub comm: String,
+    pub state: char,
+    pub ppid: u32,
+    pub pgrp: i32,
+    pub session: i32,
+    pub flags: u32,
+    pub utime: u64,
+    pub stime: u64,
+    pub nice: i32,
+    pub num_threads: u32,
+    pub starttime: u64,
+}
+
+impl ProcStat {
+    pub fn cpu_ticks(&self) -> u64 {
+        self.utime + self.stime
+    }
+
+    pub fn is_kernel_thread(&self) -> bool {
+        self.flags & PF_KTHREAD != 0
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ProcStatus {
+    pub name: String,
+    pub uid: Option<u32>,
+    pub vm_rss: u64,
+    pub vm_size: u64,
+}
+
+pub fn list_pids() -> io::Result<Vec<u32>> {
+    let mut pids: Vec<u32> = fs::read_dir("/proc")?
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+        .collect();
+    pids.sort_unstable();
+    Ok(pids)
+}
+
+// 进程下所有线程的 TID，nice、I/O 优先级和 CPU 亲和性在 Linux 上都是按线程设置的
+pub fn list_tasks(pid: u32) -> io::Result<Vec<u32>> {
+    let mut tids: Vec<u32> = fs::read_dir(format!("/proc/{}/task", pid))?
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+        .collect();
+    tids.sort_unstable();
+    Ok(tids)
+}
+
+pub fn read_stat(pid: u32) -> io::Result<ProcStat> {
+    let content = fs::read_to_string(format!("/proc/{}/stat", pid))?;
+    parse_stat(pid, &content)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("malformed /proc/{}/stat", pid)))
+}
+
+// comm 可能包含空格和括号，以最后一个 ')' 为界再按空白切分其余字段
+fn parse_stat(pid: u32, content: &str) -> Option<ProcStat> {
+    let open = content.find('(')?;
+    let close = content.rfind(')')?;
+    let comm = content.get(open + 1..close)?.to_string();
+    let fields: Vec<&str> = content.get(close + 1..)?.split_whitespace().collect();
+    let field = |index: usize| fields.get(index).copied();
+
+    Some(ProcStat {
+        pid,
+        comm,
+        state: field(0)?.chars().next()?,
+        ppid: field(1)?.parse().ok()?,
+        pgrp: field(2)?.parse().ok()?,
+        session: field(3)?.parse().ok()?,
+        flags: field(6)?.parse().ok()?,
+        utime: field(11)?.parse().ok()?,
+        stime: field(12)?.parse().ok()?,
+        nice: field(16)?.parse().ok()?,
+        num_threads: field(17)?.parse().ok()?,
+        starttime: field(19)?.parse().ok()?,
+    })
+}
+
+pub fn read_status(pid: u32) -> io::Result<ProcStatus> {
+    let content = fs::read_to_string(format!("/proc/{}/status", pid))?;
+    let mut status = ProcStatus::default();
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key {
+            "Name" => status.name = value.to_string(),
+            // 依次为 real、effective、saved、filesystem uid，取 real uid
+            "Uid" => status.uid = value.split_whitespace().next().and_then(|v| v.parse().ok()),
+            "VmRSS" => status.vm_rss = parse_kb(value),
+            "VmSize" => status.vm_size = parse_kb(value),
+            _ => {}
+        }
+    }
+    Ok(status)
+}
+
+// "1234 kB" -> 字节数
+pub fn parse_kb(value: &str) -> u64 {
+    value
+        .split_whitespace()
+        .next()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(0)
+        * 1024
+}
+
+// 参数以 NUL 分隔；内核线程和僵尸进程的 cmdline 为空
+pub fn read_cmdline(pid: u32) -> Option<String> {
+    let raw = fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    let args: Vec<String> = raw
+        .split(|b| *b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect();
+    if args.is_empty() {
+        None
+    } else {
+        Some(args.join(" "))
+    }
+}
+
+pub fn read_argv0(pid: u32) -> Option<String> {
+    let raw = fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    let first = raw.split(|b| *b == 0).next().filter(|arg| !arg.is_empty())?;
+    Some(String::from_utf8_lossy(first).into_owned())
+}
+
+// exe、cwd 等符号链接，读取其他用户的进程需要权限
+pub fn read_link(pid: u32, name: &str) -> Option<String> {
+    let target = fs::read_link(format!("/proc/{}/{}", pid, name)).ok()?;
+    Some(target.to_string_lossy().into_owned())
+}
+
+// KEY=VALUE 以 NUL 分隔；反映的是进程启动时的环境，之后 setenv 的修改看不到
+pub fn read_environ(pid: u32) -> io::Result<BTreeMap<String, String>> {
+    let raw = fs::read(format!("/proc/{}/environ", pid))?;
+    Ok(raw
+        .split(|b| *b == 0)
+        .filter_map(|entry| {
+            let entry = String::from_utf8_lossy(entry);
+            let (key, value) = entry.split_once('=')?;
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect())
+}
+
+pub fn read_fds(pid: u32) -> io::Result<Vec<OpenFile>> {
+    let mut files: Vec<OpenFile> = fs::read_dir(format!("/proc/{}/fd", pid))?
+        .flatten()
+        .filter_map(|entry| {
+            let fd = entry.file_name().to_str()?.parse().ok()?;
+            // 遍历过程中描述符可能已被关闭
+            let target = fs::read_link(entry.path()).ok()?;
+            Some(OpenFile {
+                fd,
+                target: target.to_string_lossy().into_owned(),
+            })
+        })
+        .collect();
+    files.sort_unstable_by_key(|file| file.fd);
+    Ok(files)
+}
+
+// smaps_rollup 是所有映射的汇总，比逐个解析 smaps 便宜得多（Linux 4.14+）；
+// Pss_Anon/Pss_File/Pss_Shmem 在 5.x 之后才有，旧内核上为 0
+pub fn read_smaps_rollup(pid: u32) -> io::Result<MemoryMapsSummary> {
+    let content = fs::read_to_string(format!("/proc/{}/smaps_rollup", pid))?;
+    let mut summary = MemoryMapsSummary::default();
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let field = match key {
+            "Rss" => &mut summary.rss,
+            "Pss" => &mut summary.pss,
+            "Pss_Anon" => &mut summary.pss_anon,
+            "Pss_File" => &mut summary.pss_file,
+            "Pss_Shmem" => &mut summary.pss_shmem,
+            "Shared_Clean" => &mut summary.shared_clean,
+            "Shared_Dirty" => &mut summary.shared_dirty,
+            "Private_Clean" => &mut summary.private_clean,
+            "Private_Dirty" => &mut summary.private_dirty,
+            "Anonymous" => &mut summary.anonymous,
+            "Swap" => &mut summary.swap,
+            "SwapPss" => &mut summary.swap_pss,
+            "Locked" => &mut summary.locked,
+            _ => continue,
+        };
+        *field = parse_kb(value);
+    }
+    Ok(summary)
+}
+
+// 按表头各列的起始位置切分，限制名称本身含有空格
+pub fn read_limits(pid: u32) -> io::Result<Vec<ResourceLimit>> {
+    let content = fs::read_to_string(format!("/proc/{}/limits", pid))?;
+    let mut lines = content.lines();
+    let header = lines.next().unwrap_or_default();
+    let (Some(soft_at), Some(hard_at), Some(unit_at)) =
+        (header.find("Soft Limit"), header.find("Hard Limit"), header.find("Units"))
+    else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("malformed /proc/{}/limits", pid)));
+    };
+    let column = |line: &str, start: usize, end: usize| -> String {
+        line.get(start..end.min(line.len())).unwrap_or_default().trim().to_string()
+    };
+    let value = |text: String| text.parse::<u64>().ok();
+
+    Ok(lines
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let unit = column(line, unit_at, line.len());
+            ResourceLimit {
+                name: column(line, 0, soft_at),
+                soft: value(column(line, soft_at, hard_at)),
+                hard: value(column(line, hard_at, unit_at)),
+                unit: if unit.is_empty() { None } else { Some(unit) },
+            }
+        })
+        .collect())
+}
+
+// cgroup v2 只有一行 "0::/path"；v1 每个层级一行，此时取 systemd 层级或第一行
+pub fn read_cgroup(pid: u32) -> Option<String> {
+    let content = fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    let entries: Vec<(&str, &str)> = content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, ':');
+            fields.next()?;
+            Some((fields.next()?, fields.next()?))
+        })
+        .collect();
+    entries
+        .iter()
+        .find(|(controllers, _)| controllers.is_empty())
+        .or_else(|| entries.iter().find(|(controllers, _)| *controllers == "name=systemd"))
+        .or_else(|| entries.first())
+        .map(|(_, path)| path.to_string())
+}
+
+pub fn read_task_stat(pid: u32, tid: u32) -> io::Result<ProcStat> {
+    let content = fs::read_to_string(format!("/proc/{}/task/{}/stat", pid, tid))?;
+    parse_stat(tid, &content)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("malformed /proc/{}/task/{}/stat", pid, tid)))
+}
+
+// 读取其他用户进程的 io 需要权限，读不到时返回 None
+pub fn read_io(pid: u32) -> Option<(u64, u64)> {
+    let content = fs::read_to_string(format!("/proc/{}/io", pid)).ok()?;
+    let mut read_bytes = 0;
+    let mut write_bytes = 0;
+    for line in content.lines() {
+        if let Some(value) = line.strip_prefix("read_bytes:") {
+            read_bytes = value.trim().parse().unwrap_or(0);
+        } else if let Some(value) = line.strip_prefix("write_bytes:") {
+            write_bytes = value.trim().parse().unwrap_or(0);
+        }
+    }
+    Some((read_bytes, write_bytes))
+}
+
+// 系统启动时间（Unix 秒），进程 starttime 是相对它的时钟滴答数
+pub fn boot_time() -> io::Result<u64> {
+    let content = fs::read_to_string("/proc/stat")?;
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("btime ")?.trim().parse().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "btime missing from /proc/stat"))
+}
+
+pub fn clock_ticks() -> u64 {
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks > 0 {
+        ticks as u64
+    } else {
+        100
+    }
+}
+
+// uid -> 用户名，来自 /etc/passwd；LDAP 等外部用户查不到时前端显示 uid
+pub fn user_names() -> HashMap<u32, String> {
+    let content = fs::read_to_string("/etc/passwd").unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse().ok()?;
+            Some((uid, name.to_string()))
+        })
+        .collect()
+}
+
+pub fn state_name(state: char) -> &'static str {
+    match state {
+        'R' => "Running",
+        'S' => "Sleeping",
+        'D' => "Disk Sleep",
+        'Z' => "Zombie",
+        'T' => "Stopped",
+        't' => "Tracing Stop",
+        'X' | 'x' => "Dead",
+        'I' => "Idle",
+        'K' => "Wakekill",
+        'W' => "Waking",
+        'P' => "Parked",
+        _ => "Unknown",
+    }
+}
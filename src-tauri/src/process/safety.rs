@@ -0,0 +1,169 @@
+use crate::database::DatabaseService;
+use crate::error::{DashError, DashResult};
+use std::collections::HashSet;
+
+pub const SETTING_PROTECTED_NAMES: &str = "protected_process_names";
+
+// 默认保护的进程名：会话、显示服务和远程登录，误杀后桌面或连接会直接断掉
+const DEFAULT_PROTECTED_NAMES: &[&str] = &[
+    "systemd",
+    "init",
+    "launchd",
+    "dbus-daemon",
+    "dbus-broker",
+    "Xorg",
+    "Xwayland",
+    "gnome-shell",
+    "kwin_wayland",
+    "kwin_x11",
+    "plasmashell",
+    "sway",
+    "gdm",
+    "sddm",
+    "lightdm",
+    "sshd",
+    "WindowServer",
+    "loginwindow",
+    "csrss.exe",
+    "wininit.exe",
+    "winlogon.exe",
+    "lsass.exe",
+    "services.exe",
+    "smss.exe",
+];
+
+pub fn default_protected_names() -> Vec<String> {
+    DEFAULT_PROTECTED_NAMES.iter().map(|name| name.to_string()).collect()
+}
+
+pub fn load_protected_names(db: &DatabaseService) -> DashResult<Vec<String>> {
+    Ok(db
+        .get_setting(SETTING_PROTECTED_NAMES)?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_else(default_protected_names))
+}
+
+// 去掉空白和重复项后保存，返回实际保存的列表
+pub fn save_protected_names(db: &DatabaseService, names: &[String]) -> DashResult<Vec<String>> {
+    let mut seen = HashSet::new();
+    let names: Vec<String> = names
+        .iter()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty() && seen.insert(name.clone()))
+        .collect();
+    db.set_setting(SETTING_PROTECTED_NAMES, &serde_json::to_string(&names)?)?;
+    Ok(names)
+}
+
+// 发信号前的安全检查。init、自身和内核线程无论如何都拒绝；
+// 祖先进程（终端、桌面会话等）和受保护名单可以用 force 跳过
+pub struct Guard {
+    own_pid: u32,
+    ancestors: HashSet<u32>,
+    protected_names: Vec<String>,
+}
+
+impl Guard {
+    pub fn new(protected_names: Vec<String>) -> Self {
+        let own_pid = std::process::id();
+        Self {
+            own_pid,
+            ancestors: ancestors_of(own_pid),
+            protected_names,
+        }
+    }
+
+    pub fn check(&self, pid: u32, force: bool) -> DashResult<()> {
+        let refuse = |rule: &'static str, reason: String| {
+            Err(DashError::Protected { pid, rule, reason })
+        };
+
+        if pid == 0 {
+            return Err(DashError::InvalidArgument("invalid pid".to_string()));
+        }
+        #[cfg(unix)]
+        if pid == 1 {
+            return refuse("init", "PID 1 is the init process".to_string());
+        }
+        if pid == self.own_pid {
+            return refuse("own_process", "this is the dashsys process itself".to_string());
+        }
+        #[cfg(target_os = "linux")]
+        if let Ok(stat) = crate::process::procfs::read_stat(pid) {
+            if stat.is_kernel_thread() {
+                return refuse("kernel_thread", format!("{} is a kernel thread", stat.comm));
+            }
+        }
+        if force {
+            return Ok(());
+        }
+
+        if self.ancestors.contains(&pid) {
+            return refuse(
+                "ancestor",
+                "this process is an ancestor of dashsys; pass force to override".to_string(),
+            );
+        }
+        if let Some(name) = process_names(pid)
+            .into_iter()
+            .find(|name| self.protected_names.iter().any(|protected| protected == name))
+        {
+            return refuse(
+                "protected_name",
+                format!("{} is on the protected process list; pass force to override", name),
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn ancestors_of(pid: u32) -> HashSet<u32> {
+    let mut ancestors = HashSet::new();
+    let mut current = pid;
+    while let Ok(stat) = crate::process::procfs::read_stat(current) {
+        if stat.ppid == 0 || !ancestors.insert(stat.ppid) {
+            break;
+        }
+        current = stat.ppid;
+    }
+    ancestors
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn ancestors_of(_pid: u32) -> HashSet<u32> {
+    HashSet::from([std::os::unix::process::parent_id()])
+}
+
+#[cfg(not(unix))]
+fn ancestors_of(_pid: u32) -> HashSet<u32> {
+    HashSet::new()
+}
+
+// comm 最多 15 个字符会被截断，同时比对 argv[0] 的文件名
+#[cfg(target_os = "linux")]
+fn process_names(pid: u32) -> Vec<String> {
+    let mut names = Vec::new();
+    if let Ok(stat) = crate::process::procfs::read_stat(pid) {
+        names.push(stat.comm);
+    }
+    if let Some(program) = crate::process::procfs::read_argv0(pid) {
+        if let Some(base) = std::path::Path::new(&program).file_name() {
+            names.push(base.to_string_lossy().into_owned());
+        }
+    }
+    names
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_names(pid: u32) -> Vec<String> {
+    let mut system = sysinfo::System::new();
+    let pid = sysinfo::Pid::from_u32(pid);
+    if !system.refresh_process(pid) {
+        return Vec::new();
+    }
+    system
+        .process(pid)
+        .map(|process| vec![process.name().to_string()])
+        .unwrap_or_default()
+}
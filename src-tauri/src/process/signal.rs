@@ -0,0 +1,250 @@
+use crate::error::{DashError, DashResult};
+#[cfg(unix)]
+use crate::process::os_error;
+use crate::process::safety::Guard;
+use serde::{Deserialize, Serialize};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_GRACE_PERIOD_MS: u64 = 3000;
+pub const MAX_GRACE_PERIOD_MS: u64 = 60_000;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+// SIGKILL 之后等待内核回收的时间，超过仍存在通常是卡在 D 状态
+const KILL_WAIT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Signal {
+    #[serde(rename = "SIGHUP")]
+    Hup,
+    #[serde(rename = "SIGINT")]
+    Int,
+    #[serde(rename = "SIGQUIT")]
+    Quit,
+    #[serde(rename = "SIGKILL")]
+    Kill,
+    #[serde(rename = "SIGUSR1")]
+    Usr1,
+    #[serde(rename = "SIGUSR2")]
+    Usr2,
+    #[serde(rename = "SIGTERM")]
+    Term,
+    #[serde(rename = "SIGCONT")]
+    Cont,
+    #[serde(rename = "SIGSTOP")]
+    Stop,
+}
+
+// 优雅终止是在哪一步结束的
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminateStep {
+    Sigterm,
+    Sigkill,
+    Survived,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminateReport {
+    pub pid: u32,
+    pub ended_by: TerminateStep,
+    pub elapsed_ms: u64,
+}
+
+// 对进程树中单个进程发信号的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalOutcome {
+    Signalled,
+    Exited,
+    PermissionDenied,
+    Protected,
+    Failed,
+}
+
+impl SignalOutcome {
+    pub fn of(result: DashResult<()>) -> (Self, Option<String>) {
+        match result {
+            Ok(()) => (SignalOutcome::Signalled, None),
+            Err(DashError::NotFound(_)) => (SignalOutcome::Exited, None),
+            Err(e @ DashError::PermissionDenied(_)) => (SignalOutcome::PermissionDenied, Some(e.to_string())),
+            Err(e @ DashError::Protected { .. }) => (SignalOutcome::Protected, Some(e.to_string())),
+            Err(e) => (SignalOutcome::Failed, Some(e.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeKillEntry {
+    pub pid: u32,
+    pub parent: Option<u32>,
+    pub name: String,
+    pub depth: u32,
+    pub outcome: SignalOutcome,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeKillReport {
+    pub root: u32,
+    pub signal: Signal,
+    pub processes: Vec<TreeKillEntry>,
+}
+
+impl Signal {
+    pub fn name(self) -> &'static str {
+        match self {
+            Signal::Hup => "SIGHUP",
+            Signal::Int => "SIGINT",
+            Signal::Quit => "SIGQUIT",
+            Signal::Kill => "SIGKILL",
+            Signal::Usr1 => "SIGUSR1",
+            Signal::Usr2 => "SIGUSR2",
+            Signal::Term => "SIGTERM",
+            Signal::Cont => "SIGCONT",
+            Signal::Stop => "SIGSTOP",
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Signal {
+    fn raw(self) -> libc::c_int {
+        match self {
+            Signal::Hup => libc::SIGHUP,
+            Signal::Int => libc::SIGINT,
+            Signal::Quit => libc::SIGQUIT,
+            Signal::Kill => libc::SIGKILL,
+            Signal::Usr1 => libc::SIGUSR1,
+            Signal::Usr2 => libc::SIGUSR2,
+            Signal::Term => libc::SIGTERM,
+            Signal::Cont => libc::SIGCONT,
+            Signal::Stop => libc::SIGSTOP,
+        }
+    }
+}
+
+// pid 必须为正数：kill(0) / kill(-1) / 负数会把信号发给整个进程组甚至所有进程
+#[cfg(unix)]
+pub(crate) fn validate_pid(pid: u32) -> DashResult<libc::pid_t> {
+    match libc::pid_t::try_from(pid) {
+        Ok(raw) if raw > 0 => Ok(raw),
+        _ => Err(DashError::InvalidArgument(format!("invalid pid: {}", pid))),
+    }
+}
+
+#[cfg(unix)]
+pub fn send(pid: u32, signal: Signal) -> DashResult<()> {
+    let raw_pid = validate_pid(pid)?;
+    if unsafe { libc::kill(raw_pid, signal.raw()) } == 0 {
+        return Ok(());
+    }
+    let err = std::io::Error::last_os_error();
+    Err(os_error(err, pid, &format!("send {} to", signal.name())))
+}
+
+#[cfg(not(unix))]
+pub fn send(_pid: u32, _signal: Signal) -> DashResult<()> {
+    Err(DashError::Unsupported(
+        "Signals are not supported on this OS".to_string(),
+    ))
+}
+
+// kill_process 和监视规则共用的强制结束，只结束该进程本身（Windows 沿用 taskkill /T）；
+// 需要连同子进程一起结束时使用 tree::kill_tree
+pub fn kill(pid: u32, guard: &Guard, force: bool) -> DashResult<()> {
+    guard.check(pid, force)?;
+
+    #[cfg(target_os = "windows")]
+    {
+        let status = std::process::Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .status()?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(DashError::CommandFailed(format!("taskkill failed: {status}")))
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        send(pid, Signal::Kill)
+    }
+}
+
+// 先发 SIGTERM 并在宽限期内轮询，进程仍在时再升级为 SIGKILL
+pub fn terminate(pid: u32, grace_period: Duration) -> DashResult<TerminateReport> {
+    let started = Instant::now();
+    let identity = identity(pid);
+    let report = |ended_by| TerminateReport {
+        pid,
+        ended_by,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    };
+
+    send(pid, Signal::Term)?;
+    if wait_for_exit(pid, identity, grace_period) {
+        return Ok(report(TerminateStep::Sigterm));
+    }
+
+    match send(pid, Signal::Kill) {
+        Ok(()) => {}
+        // 恰好在宽限期结束时自行退出
+        Err(DashError::NotFound(_)) => return Ok(report(TerminateStep::Sigterm)),
+        Err(e) => return Err(e),
+    }
+    if wait_for_exit(pid, identity, KILL_WAIT) {
+        Ok(report(TerminateStep::Sigkill))
+    } else {
+        Ok(report(TerminateStep::Survived))
+    }
+}
+
+fn wait_for_exit(pid: u32, identity: Option<u64>, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if !is_alive(pid, identity) {
+            return true;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return false;
+        }
+        thread::sleep(POLL_INTERVAL.min(deadline - now));
+    }
+}
+
+// 用进程启动时间识别同一个进程，避免等待期间 PID 被复用后误判为仍在运行
+#[cfg(target_os = "linux")]
+fn identity(pid: u32) -> Option<u64> {
+    crate::process::procfs::read_stat(pid).ok().map(|stat| stat.starttime)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn identity(_pid: u32) -> Option<u64> {
+    None
+}
+
+// 僵尸进程已经退出，只是还没被父进程回收，kill(pid, 0) 对它仍然成功
+#[cfg(target_os = "linux")]
+fn is_alive(pid: u32, identity: Option<u64>) -> bool {
+    match crate::process::procfs::read_stat(pid) {
+        Ok(stat) => stat.state != 'Z' && identity.is_none_or(|start| start == stat.starttime),
+        Err(_) => false,
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn is_alive(pid: u32, _identity: Option<u64>) -> bool {
+    let Ok(raw_pid) = validate_pid(pid) else {
+        return false;
+    };
+    unsafe { libc::kill(raw_pid, 0) == 0 }
+        || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn is_alive(_pid: u32, _identity: Option<u64>) -> bool {
+    false
+}
@@ -0,0 +1,190 @@
+use crate::error::{DashError, DashResult};
+use crate::process::safety::Guard;
+use crate::process::signal::{self, Signal, SignalOutcome, TreeKillEntry, TreeKillReport};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+// 关闭窗口时仍有被暂停的进程，窗口暂不关闭，由前端确认后再次关闭
+pub const SUSPENDED_ON_EXIT_EVENT: &str = "process://suspended-on-exit";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspendedProcess {
+    pub pid: u32,
+    pub name: String,
+    // 发起暂停时选中的进程，整棵树暂停时子进程记录的是树根
+    pub root: u32,
+    pub suspended_at: i64,
+    #[serde(skip)]
+    starttime: Option<u64>,
+}
+
+// 记录由 dashsys 暂停的进程，便于列出、全部恢复以及退出前提醒
+#[derive(Default)]
+pub struct SuspendState {
+    suspended: Mutex<BTreeMap<u32, SuspendedProcess>>,
+    warned: AtomicBool,
+}
+
+impl SuspendState {
+    // 自上而下发 SIGSTOP，先冻结父进程，避免它在子进程被暂停的同时继续 fork
+    pub fn suspend(&self, root: u32, tree: bool, guard: &Guard, force: bool) -> DashResult<TreeKillReport> {
+        let mut plan = targets(root, tree)?;
+        guard.check(root, force)?;
+        plan.reverse();
+
+        let now = chrono::Utc::now().timestamp();
+        let mut suspended = self.suspended.lock().unwrap();
+        let mut processes = Vec::with_capacity(plan.len());
+        for (pid, depth) in plan {
+            let info = describe(pid);
+            let result = guard.check(pid, force).and_then(|_| signal::send(pid, Signal::Stop));
+            let (outcome, error) = SignalOutcome::of(result);
+            if outcome == SignalOutcome::Signalled {
+                suspended.insert(
+                    pid,
+                    SuspendedProcess {
+                        pid,
+                        name: info.name.clone(),
+                        root,
+                        suspended_at: now,
+                        starttime: info.starttime,
+                    },
+                );
+            }
+            processes.push(entry(pid, depth, info, outcome, error));
+        }
+        self.warned.store(false, Ordering::Relaxed);
+
+        Ok(TreeKillReport {
+            root,
+            signal: Signal::Stop,
+            processes,
+        })
+    }
+
+    // 恢复不做安全检查：SIGCONT 不会破坏任何进程，也要能恢复在 dashsys 之外被暂停的进程
+    pub fn resume(&self, root: u32, tree: bool) -> DashResult<TreeKillReport> {
+        let mut plan = targets(root, tree)?;
+        let mut suspended = self.suspended.lock().unwrap();
+        if tree {
+            // 已经被重新挂到别处的子进程也一起恢复
+            for process in suspended.values().filter(|p| p.root == root) {
+                if !plan.iter().any(|(pid, _)| *pid == process.pid) {
+                    plan.push((process.pid, 1));
+                }
+            }
+        }
+
+        let processes = plan
+            .into_iter()
+            .map(|(pid, depth)| continue_process(&mut suspended, pid, depth))
+            .collect();
+        Ok(TreeKillReport {
+            root,
+            signal: Signal::Cont,
+            processes,
+        })
+    }
+
+    pub fn resume_all(&self) -> Vec<TreeKillEntry> {
+        let mut suspended = self.suspended.lock().unwrap();
+        let pids: Vec<u32> = suspended.keys().copied().collect();
+        pids.into_iter()
+            .map(|pid| continue_process(&mut suspended, pid, 0))
+            .collect()
+    }
+
+    // 去掉已经退出、PID 被复用或已在别处恢复运行的进程
+    pub fn list(&self) -> Vec<SuspendedProcess> {
+        let mut suspended = self.suspended.lock().unwrap();
+        suspended.retain(|pid, process| still_suspended(*pid, process.starttime));
+        suspended.values().cloned().collect()
+    }
+
+    // 每次暂停后只提醒一次
+    pub fn should_warn_on_exit(&self) -> bool {
+        !self.list().is_empty() && !self.warned.swap(true, Ordering::Relaxed)
+    }
+}
+
+fn continue_process(suspended: &mut BTreeMap<u32, SuspendedProcess>, pid: u32, depth: u32) -> TreeKillEntry {
+    let info = describe(pid);
+    let (outcome, error) = SignalOutcome::of(signal::send(pid, Signal::Cont));
+    if matches!(outcome, SignalOutcome::Signalled | SignalOutcome::Exited) {
+        suspended.remove(&pid);
+    }
+    entry(pid, depth, info, outcome, error)
+}
+
+fn entry(pid: u32, depth: u32, info: ProcessDescription, outcome: SignalOutcome, error: Option<String>) -> TreeKillEntry {
+    TreeKillEntry {
+        pid,
+        parent: info.parent,
+        name: info.name,
+        depth,
+        outcome,
+        error,
+    }
+}
+
+#[derive(Default)]
+struct ProcessDescription {
+    name: String,
+    parent: Option<u32>,
+    starttime: Option<u64>,
+}
+
+// 返回 (pid, depth)，深度大的在前
+#[cfg(target_os = "linux")]
+fn targets(root: u32, tree: bool) -> DashResult<Vec<(u32, u32)>> {
+    if crate::process::procfs::read_stat(root).is_err() {
+        return Err(DashError::NotFound(format!("process {} not found", root)));
+    }
+    if tree {
+        crate::process::tree::descendants(root)
+    } else {
+        Ok(vec![(root, 0)])
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn targets(root: u32, tree: bool) -> DashResult<Vec<(u32, u32)>> {
+    if tree {
+        return Err(DashError::Unsupported(
+            "Suspending a process tree is only implemented on Linux".to_string(),
+        ));
+    }
+    Ok(vec![(root, 0)])
+}
+
+#[cfg(target_os = "linux")]
+fn describe(pid: u32) -> ProcessDescription {
+    match crate::process::procfs::read_stat(pid) {
+        Ok(stat) => ProcessDescription {
+            name: stat.comm,
+            parent: Some(stat.ppid).filter(|ppid| *ppid != 0),
+            starttime: Some(stat.starttime),
+        },
+        Err(_) => ProcessDescription::default(),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn describe(_pid: u32) -> ProcessDescription {
+    ProcessDescription::default()
+}
+
+#[cfg(target_os = "linux")]
+fn still_suspended(pid: u32, starttime: Option<u64>) -> bool {
+    match crate::process::procfs::read_stat(pid) {
+        Ok(stat) => stat.state == 'T' && starttime.is_none_or(|start| start == stat.starttime),
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn still_suspended(_pid: u32, _starttime: Option<u64>) -> bool {
+    true
+}
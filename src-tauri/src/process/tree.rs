@@ -0,0 +1,116 @@
+use crate::error::{DashError, DashResult};
+use crate::process::procfs;
+use crate::process::safety::Guard;
+use crate::process::signal::{self, Signal, SignalOutcome, TreeKillEntry, TreeKillReport};
+use std::collections::{HashMap, HashSet};
+
+// 第一轮之后最多再扫描的次数
+const RESCAN_ROUNDS: usize = 2;
+
+struct Node {
+    parent: u32,
+    name: String,
+}
+
+// 当前所有进程的 ppid 关系
+fn snapshot() -> DashResult<HashMap<u32, Node>> {
+    let mut nodes = HashMap::new();
+    for pid in procfs::list_pids()? {
+        if let Ok(stat) = procfs::read_stat(pid) {
+            nodes.insert(
+                pid,
+                Node {
+                    parent: stat.ppid,
+                    name: stat.comm,
+                },
+            );
+        }
+    }
+    Ok(nodes)
+}
+
+// 以 root 为根的子树（含 root），返回 (pid, depth)，深度大的排在前面
+pub fn descendants(root: u32) -> DashResult<Vec<(u32, u32)>> {
+    let nodes = snapshot()?;
+    Ok(walk(&nodes, &[(root, 0)]))
+}
+
+// 从若干起点（带各自深度）向下展开，结果包含起点本身
+fn walk(nodes: &HashMap<u32, Node>, roots: &[(u32, u32)]) -> Vec<(u32, u32)> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (pid, node) in nodes {
+        children.entry(node.parent).or_default().push(*pid);
+    }
+
+    let mut visited: HashSet<u32> = roots.iter().map(|(pid, _)| *pid).collect();
+    let mut result = roots.to_vec();
+    let mut index = 0;
+    while index < result.len() {
+        let (pid, depth) = result[index];
+        for kid in children.get(&pid).into_iter().flatten() {
+            if visited.insert(*kid) {
+                result.push((*kid, depth + 1));
+            }
+        }
+        index += 1;
+    }
+    // 同一深度内按 PID 倒序，较新的进程先处理
+    result.sort_by(|a, b| b.1.cmp(&a.1).then(b.0.cmp(&a.0)));
+    result
+}
+
+// 自底向上发信号：先处理最深的子进程，最后才是 root，避免子进程被 init 收养后脱离这棵树。
+// root 未通过安全检查时直接报错，子进程未通过时跳过并记录在报告里
+pub fn kill_tree(root: u32, signal: Signal, guard: &Guard, force: bool) -> DashResult<TreeKillReport> {
+    let mut nodes = snapshot()?;
+    if !nodes.contains_key(&root) {
+        return Err(DashError::NotFound(format!("process {} not found", root)));
+    }
+    guard.check(root, force)?;
+
+    let mut handled: Vec<(u32, u32)> = Vec::new();
+    let mut processes = Vec::new();
+    let mut plan = walk(&nodes, &[(root, 0)]);
+
+    for round in 0..=RESCAN_ROUNDS {
+        for (pid, depth) in plan {
+            let node = nodes.get(&pid);
+            let result = guard.check(pid, force).and_then(|_| signal::send(pid, signal));
+            let (outcome, error) = SignalOutcome::of(result);
+            processes.push(TreeKillEntry {
+                pid,
+                parent: node.map(|n| n.parent).filter(|ppid| *ppid != 0),
+                name: node.map(|n| n.name.clone()).unwrap_or_default(),
+                depth,
+                outcome,
+                error,
+            });
+            handled.push((pid, depth));
+        }
+        if round == RESCAN_ROUNDS {
+            break;
+        }
+
+        // 重新扫描仍存在的已处理进程，补上发信号期间新 fork 出来的子进程
+        nodes = snapshot()?;
+        let seen: HashSet<u32> = handled.iter().map(|(pid, _)| *pid).collect();
+        let alive: Vec<(u32, u32)> = handled
+            .iter()
+            .copied()
+            .filter(|(pid, _)| nodes.contains_key(pid))
+            .collect();
+        plan = walk(&nodes, &alive)
+            .into_iter()
+            .filter(|(pid, _)| !seen.contains(pid))
+            .collect();
+        if plan.is_empty() {
+            break;
+        }
+    }
+
+    Ok(TreeKillReport {
+        root,
+        signal,
+        processes,
+    })
+}
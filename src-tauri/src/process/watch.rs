@@ -0,0 +1,333 @@
+use crate::database::service::WatchTriggerRecord;
+use crate::database::DatabaseService;
+use crate::error::{DashError, DashResult};
+use crate::process::audit::PendingAction;
+use crate::process::safety::{self, Guard};
+use crate::process::signal::{self, Signal};
+use crate::types::ProcessInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+pub const SETTING_WATCH_RULES: &str = "process_watch_rules";
+// 每次规则触发都会发出，前端据此弹出通知
+pub const WATCH_TRIGGERED_EVENT: &str = "process://watch-triggered";
+pub const MAX_RULES: usize = 100;
+pub const DEFAULT_QUERY_LIMIT: u32 = 500;
+pub const MAX_QUERY_LIMIT: u32 = 5000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WatchCondition {
+    // 常驻内存 (RSS) 超过阈值
+    MemoryAbove { bytes: u64 },
+    CpuAbove { percent: f32 },
+    // 之前匹配到的进程消失
+    Exited,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WatchAction {
+    Notify,
+    Signal { signal: Signal },
+    // tree 为 true 时连同子进程一起结束；旧版本保存的规则没有该字段，按原来的整树结束处理
+    Kill {
+        #[serde(default = "default_kill_tree")]
+        tree: bool,
+    },
+}
+
+fn default_kill_tree() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerOutcome {
+    Notified,
+    DryRun,
+    Success,
+    Failed,
+    Refused,
+}
+
+impl TriggerOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TriggerOutcome::Notified => "notified",
+            TriggerOutcome::DryRun => "dry_run",
+            TriggerOutcome::Success => "success",
+            TriggerOutcome::Failed => "failed",
+            TriggerOutcome::Refused => "refused",
+        }
+    }
+}
+
+// 进程名精确匹配、命令行包含匹配，两者都给出时需同时满足
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchRule {
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub process_name: Option<String>,
+    #[serde(default)]
+    pub command_contains: Option<String>,
+    pub condition: WatchCondition,
+    // 条件需要持续成立的时间，Exited 忽略此项
+    #[serde(default)]
+    pub duration_secs: u64,
+    pub action: WatchAction,
+    // 只记录和通知，不真正执行动作
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl WatchRule {
+    fn matches(&self, process: &ProcessInfo) -> bool {
+        let name_matches = self.process_name.as_ref().is_none_or(|name| *name == process.name);
+        let command_matches = self.command_contains.as_ref().is_none_or(|needle| {
+            process.command.as_ref().is_some_and(|command| command.contains(needle.as_str()))
+        });
+        name_matches && command_matches
+    }
+
+    fn breached(&self, process: &ProcessInfo) -> bool {
+        match self.condition {
+            WatchCondition::MemoryAbove { bytes } => process.memory > bytes,
+            WatchCondition::CpuAbove { percent } => process.cpu_usage > percent,
+            WatchCondition::Exited => false,
+        }
+    }
+
+    fn validate(&self) -> DashResult<()> {
+        let invalid = |message: &str| Err(DashError::InvalidArgument(format!("rule \"{}\": {}", self.name, message)));
+        let blank = |value: &Option<String>| value.as_ref().is_none_or(|v| v.trim().is_empty());
+        if blank(&self.process_name) && blank(&self.command_contains) {
+            return invalid("process_name or command_contains is required");
+        }
+        match (&self.condition, &self.action) {
+            (WatchCondition::MemoryAbove { bytes: 0 }, _) => invalid("memory threshold must be greater than 0"),
+            (WatchCondition::CpuAbove { percent }, _) if percent.is_nan() || *percent <= 0.0 => {
+                invalid("cpu threshold must be greater than 0")
+            }
+            (WatchCondition::Exited, WatchAction::Signal { .. } | WatchAction::Kill { .. }) => {
+                invalid("an exited process can only be notified about")
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// 规则触发时的上下文，交给 execute 执行动作并记录
+#[derive(Debug, Clone)]
+pub struct Trigger {
+    pub rule: WatchRule,
+    pub pid: u32,
+    pub process_name: String,
+}
+
+pub fn load_rules(db: &DatabaseService) -> DashResult<Vec<WatchRule>> {
+    Ok(db
+        .get_setting(SETTING_WATCH_RULES)?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+pub fn validate_rules(rules: &[WatchRule]) -> DashResult<()> {
+    if rules.len() > MAX_RULES {
+        return Err(DashError::InvalidArgument(format!("at most {} watch rules are allowed", MAX_RULES)));
+    }
+    let mut names = HashSet::new();
+    for rule in rules {
+        if rule.name.trim().is_empty() {
+            return Err(DashError::InvalidArgument("rule name must not be empty".to_string()));
+        }
+        if !names.insert(rule.name.as_str()) {
+            return Err(DashError::InvalidArgument(format!("duplicate rule name: {}", rule.name)));
+        }
+        rule.validate()?;
+    }
+    Ok(())
+}
+
+// 同一进程用 (pid, start_time) 区分，避免 PID 复用后沿用旧进程的状态
+type ProcessKey = (u32, u64);
+
+#[derive(Default)]
+struct RuleState {
+    // 条件开始成立的时间，以及这一轮超限是否已经触发过
+    breaches: HashMap<ProcessKey, (i64, bool)>,
+    // 上一次采样时匹配到的进程，用于发现退出
+    running: HashMap<ProcessKey, String>,
+}
+
+// 规则保存在设置表中，这里缓存一份并保存每条规则的评估状态
+#[derive(Default)]
+pub struct WatchEngine {
+    rules: Mutex<Vec<WatchRule>>,
+    states: Mutex<HashMap<String, RuleState>>,
+}
+
+impl WatchEngine {
+    pub fn new(rules: Vec<WatchRule>) -> Self {
+        Self {
+            rules: Mutex::new(rules),
+            states: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn rules(&self) -> Vec<WatchRule> {
+        self.rules.lock().unwrap().clone()
+    }
+
+    pub fn has_enabled_rules(&self) -> bool {
+        self.rules.lock().unwrap().iter().any(|rule| rule.enabled)
+    }
+
+    // 规则修改后评估状态全部重新开始
+    pub fn set_rules(&self, db: &DatabaseService, rules: Vec<WatchRule>) -> DashResult<Vec<WatchRule>> {
+        validate_rules(&rules)?;
+        db.set_setting(SETTING_WATCH_RULES, &serde_json::to_string(&rules)?)?;
+        *self.rules.lock().unwrap() = rules.clone();
+        self.states.lock().unwrap().clear();
+        Ok(rules)
+    }
+
+    // 用本次采样的进程列表评估所有启用的规则，返回需要执行的触发
+    pub fn evaluate(&self, processes: &[ProcessInfo], now: i64) -> Vec<Trigger> {
+        let rules = self.rules.lock().unwrap();
+        let mut states = self.states.lock().unwrap();
+        states.retain(|name, _| rules.iter().any(|rule| rule.enabled && rule.name == *name));
+
+        let mut triggers = Vec::new();
+        for rule in rules.iter().filter(|rule| rule.enabled) {
+            let state = states.entry(rule.name.clone()).or_default();
+            let matched: HashMap<ProcessKey, &ProcessInfo> = processes
+                .iter()
+                .filter(|process| rule.matches(process))
+                .map(|process| ((process.pid, process.start_time), process))
+                .collect();
+
+            if rule.condition == WatchCondition::Exited {
+                for (key, name) in &state.running {
+                    if !matched.contains_key(key) {
+                        triggers.push(Trigger {
+                            rule: rule.clone(),
+                            pid: key.0,
+                            process_name: name.clone(),
+                        });
+                    }
+                }
+                state.running = matched.iter().map(|(key, process)| (*key, process.name.clone())).collect();
+                continue;
+            }
+
+            // 条件不再成立或进程已退出时清掉记录，下一次超限重新计时
+            state
+                .breaches
+                .retain(|key, _| matched.get(key).is_some_and(|process| rule.breached(process)));
+            for (key, process) in &matched {
+                if !rule.breached(process) {
+                    continue;
+                }
+                let (since, fired) = state.breaches.entry(*key).or_insert((now, false));
+                if !*fired && now - *since >= rule.duration_secs as i64 {
+                    *fired = true;
+                    triggers.push(Trigger {
+                        rule: rule.clone(),
+                        pid: process.pid,
+                        process_name: process.name.clone(),
+                    });
+                }
+            }
+        }
+        triggers
+    }
+}
+
+// 执行触发的动作并写入 watch_triggers；真正发出的信号同时进入进程操作审计日志。
+// 自动动作从不使用 force，受保护的进程一律拒绝
+pub fn execute(db: &DatabaseService, trigger: &Trigger, now: i64) -> WatchTriggerRecord {
+    let rule = &trigger.rule;
+    let pid = trigger.pid;
+    let (outcome, error) = if rule.dry_run {
+        (TriggerOutcome::DryRun, None)
+    } else {
+        match rule.action {
+            WatchAction::Notify => (TriggerOutcome::Notified, None),
+            WatchAction::Signal { signal } => run(db, "watch_signal", pid, signal, |guard| {
+                guard.check(pid, false).and_then(|_| signal::send(pid, signal))
+            }),
+            WatchAction::Kill { tree: false } => {
+                run(db, "watch_kill", pid, Signal::Kill, |guard| signal::kill(pid, guard, false))
+            }
+            WatchAction::Kill { tree: true } => {
+                run(db, "watch_kill_tree", pid, Signal::Kill, |guard| kill_tree(pid, guard))
+            }
+        }
+    };
+
+    let mut record = WatchTriggerRecord {
+        id: 0,
+        timestamp: now,
+        rule_name: rule.name.clone(),
+        pid,
+        process_name: trigger.process_name.clone(),
+        condition: serde_json::to_string(&rule.condition).unwrap_or_default(),
+        action: serde_json::to_string(&rule.action).unwrap_or_default(),
+        dry_run: rule.dry_run,
+        outcome: outcome.as_str().to_string(),
+        error,
+    };
+    match db.insert_watch_trigger(&record) {
+        Ok(id) => record.id = id,
+        Err(e) => eprintln!("process watch: failed to record trigger of {}: {}", record.rule_name, e),
+    }
+    record
+}
+
+// 以树根的结果作为动作的结果，子进程各自的结果只在 kill_tree 的报告里
+#[cfg(target_os = "linux")]
+fn kill_tree(pid: u32, guard: &Guard) -> DashResult<()> {
+    use crate::process::signal::SignalOutcome;
+
+    let report = crate::process::tree::kill_tree(pid, Signal::Kill, guard, false)?;
+    match report.processes.iter().find(|entry| entry.pid == pid) {
+        Some(entry) if entry.outcome == SignalOutcome::PermissionDenied => {
+            Err(DashError::PermissionDenied(entry.error.clone().unwrap_or_default()))
+        }
+        Some(entry) if entry.outcome == SignalOutcome::Failed => {
+            Err(DashError::CommandFailed(entry.error.clone().unwrap_or_default()))
+        }
+        _ => Ok(()),
+    }
+}
+
+// Windows 的 taskkill /T 本身就会结束整棵树，其他平台只能结束该进程
+#[cfg(not(target_os = "linux"))]
+fn kill_tree(pid: u32, guard: &Guard) -> DashResult<()> {
+    signal::kill(pid, guard, false)
+}
+
+fn run(
+    db: &DatabaseService,
+    action: &'static str,
+    pid: u32,
+    signal: Signal,
+    send: impl FnOnce(&Guard) -> DashResult<()>,
+) -> (TriggerOutcome, Option<String>) {
+    let pending = PendingAction::begin(action, pid, Some(signal), false);
+    let result = safety::load_protected_names(db).and_then(|names| send(&Guard::new(names)));
+    pending.finish(db, &result);
+    match result {
+        Ok(()) => (TriggerOutcome::Success, None),
+        Err(e @ DashError::Protected { .. }) => (TriggerOutcome::Refused, Some(e.to_string())),
+        Err(e) => (TriggerOutcome::Failed, Some(e.to_string())),
+    }
+}
@@ -1,4 +1,7 @@
-use serde::{Serialize, Deserialize};
+use crate::database::service::MemoryBreakdown;
+use crate::error::DashError;
+use serde::Serialize;
+use std::collections::BTreeMap;
 
 #[derive(Serialize, Clone)]
 pub struct ApiResponse<T> {
@@ -6,20 +9,57 @@ pub struct ApiResponse<T> {
     pub timestamp: u64,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct CpuStats {
     pub usage: f32,           // 0-100
     pub frequency: u64,       // MHz
     pub core_count: usize,
     pub per_core: Vec<f32>,   // 各核心使用率
     pub load_avg: [f64; 3],   // 1min, 5min, 15min
+    pub running_tasks: Option<u32>, // /proc/loadavg 中可运行的调度实体数，非 Linux 为 None
+    pub total_tasks: Option<u32>,
+    pub frequencies: Vec<CoreFrequency>, // 没有 cpufreq 驱动（如部分虚拟机）时为空
+}
+
+// 频率单位 MHz；min/max 为硬件范围，governor 为当前调频策略
+#[derive(Debug, Serialize, Clone)]
+pub struct CoreFrequency {
+    pub core: u32,
+    pub current: u64,
+    pub min: u64,
+    pub max: u64,
+    pub governor: Option<String>,
+}
+
+// /proc/meminfo 的完整拆分，单位字节（大页数量除外）
+#[derive(Debug, Serialize, Clone)]
+pub struct MemoryDetails {
+    pub total: u64,
+    pub free: u64,
+    pub available: u64,
+    pub swap_total: u64,
+    pub swap_free: u64,
+    #[serde(flatten)]
+    pub breakdown: MemoryBreakdown,
+    pub zram_devices: Vec<ZramDevice>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ZramDevice {
+    pub name: String,
+    pub disk_size: u64,
+    pub original_bytes: u64,    // 存入的原始数据量
+    pub compressed_bytes: u64,
+    pub memory_used: u64,       // 含分配器开销的实际内存占用
+    pub memory_limit: Option<u64>, // 0 表示不限制，返回 None
+    pub algorithm: Option<String>,
 }
 
 #[derive(Serialize, Clone)]
 pub struct ProcessInfo {
     pub pid: u32,
     pub name: String,
-    pub status: String,
+    pub status: String,       // Running / Sleeping / Zombie ...
     pub cpu_usage: f32,
     pub memory: u64,
     pub virtual_memory: u64,
@@ -27,7 +67,68 @@ pub struct ProcessInfo {
     pub disk_written: u64,
     pub parent: Option<u32>,
     pub command: Option<String>,
+    pub user: Option<String>,
+    pub uid: Option<u32>,
+    pub start_time: u64,      // Unix 秒
+    pub threads: u32,
+}
+
+// 进程详情：在列表行的基础上补充只有单个进程才值得读取的信息；
+// 读取其他用户的进程时部分字段没有权限，返回 None
+#[derive(Serialize, Clone)]
+pub struct ProcessDetails {
+    #[serde(flatten)]
+    pub info: ProcessInfo,
+    pub executable: Option<String>,
+    pub working_directory: Option<String>,
+    pub environment: Option<BTreeMap<String, String>>,
+    pub open_files: Option<Vec<OpenFile>>,
+    pub memory_maps: Option<MemoryMapsSummary>,
+    pub limits: Option<Vec<ResourceLimit>>,
+    pub cgroup: Option<String>,
+    pub thread_list: Vec<ThreadInfo>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct OpenFile {
+    pub fd: u32,
+    pub target: String,       // 文件路径，或 socket:[inode]、pipe:[inode] 等
+}
+
+// 来自 /proc/<pid>/smaps_rollup，单位字节；pss_anon/file/shmem 按映射类型拆分 PSS
+#[derive(Serialize, Clone, Default)]
+pub struct MemoryMapsSummary {
+    pub rss: u64,
+    pub pss: u64,
+    pub pss_anon: u64,
+    pub pss_file: u64,
+    pub pss_shmem: u64,
+    pub shared_clean: u64,
+    pub shared_dirty: u64,
+    pub private_clean: u64,
+    pub private_dirty: u64,
+    pub anonymous: u64,
+    pub swap: u64,
+    pub swap_pss: u64,
+    pub locked: u64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ResourceLimit {
+    pub name: String,         // Max open files ...
+    pub soft: Option<u64>,    // None 表示 unlimited
+    pub hard: Option<u64>,
+    pub unit: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ThreadInfo {
+    pub tid: u32,
+    pub name: String,
+    pub status: String,
+    pub cpu_time: f64,        // 用户态 + 内核态，秒
+    pub nice: i32,
 }
 
 // 错误处理统一包装
-pub type CommandResult<T> = Result<ApiResponse<T>, String>;
+pub type CommandResult<T> = Result<ApiResponse<T>, DashError>;
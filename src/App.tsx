@@ -4,7 +4,7 @@ import { CpuMonitor } from "@/features/cpu";
 import { MemoryMonitor } from "@/features/memory";
 import { DiskMonitor } from "@/features/disk";
 import { NetworkMonitor } from "@/features/network";
-import { ProcessMonitor } from "@/features/processes";
+import { ProcessMonitor, SuspendedExitDialog } from "@/features/processes";
 import { SystemDetail } from "@/features/system";
 import { HistoryPanel } from "@/features/history";
 import { AppLayout } from "@/components/layout/AppLayout";
@@ -40,6 +40,7 @@ function App() {
         {section === "history" ? <HistoryPanel /> : null}
         {section === "system" ? <SystemDetail /> : null}
       </div>
+      <SuspendedExitDialog />
       <Toaster />
     </AppLayout>
   );
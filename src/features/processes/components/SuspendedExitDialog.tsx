@@ -0,0 +1,77 @@
+import React, { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { toast } from "sonner";
+import { invokeCommand } from "@/lib/tauri";
+import type { SuspendedProcess } from "@/types/system";
+import {
+  AlertDialog,
+  AlertDialogAction,
+  AlertDialogCancel,
+  AlertDialogContent,
+  AlertDialogDescription,
+  AlertDialogFooter,
+  AlertDialogHeader,
+  AlertDialogTitle,
+} from "@/components/ui/alert-dialog";
+
+// 后端在关闭窗口时发现仍有被暂停的进程，会拦下关闭并发出这个事件
+const SUSPENDED_ON_EXIT_EVENT = "process://suspended-on-exit";
+
+export const SuspendedExitDialog: React.FC = () => {
+  const [suspended, setSuspended] = useState<SuspendedProcess[]>([]);
+
+  useEffect(() => {
+    const unlisten = listen<SuspendedProcess[]>(SUSPENDED_ON_EXIT_EVENT, (event) => {
+      setSuspended(event.payload);
+    });
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // 同一次暂停只提醒一次，再次关闭时后端不再拦截
+  const quit = () => {
+    setSuspended([]);
+    void getCurrentWindow().close();
+  };
+
+  const resumeAllAndQuit = async () => {
+    try {
+      await invokeCommand("resume_all_processes");
+      quit();
+    } catch (e) {
+      const message = e instanceof Error ? e.message : String(e);
+      toast.error(message);
+    }
+  };
+
+  return (
+    <AlertDialog open={suspended.length > 0} onOpenChange={(open) => !open && setSuspended([])}>
+      <AlertDialogContent>
+        <AlertDialogHeader>
+          <AlertDialogTitle>Suspended processes are still stopped</AlertDialogTitle>
+          <AlertDialogDescription>
+            {suspended.length} process(es) suspended by dashsys will stay stopped after it quits.
+          </AlertDialogDescription>
+        </AlertDialogHeader>
+        <ul className="max-h-48 overflow-y-auto font-mono text-sm">
+          {suspended.map((p) => (
+            <li key={p.pid}>
+              {p.pid} {p.name}
+            </li>
+          ))}
+        </ul>
+        <AlertDialogFooter>
+          <AlertDialogCancel>Cancel</AlertDialogCancel>
+          <AlertDialogAction variant="outline" onClick={quit}>
+            Quit anyway
+          </AlertDialogAction>
+          <AlertDialogAction onClick={() => void resumeAllAndQuit()}>
+            Resume all and quit
+          </AlertDialogAction>
+        </AlertDialogFooter>
+      </AlertDialogContent>
+    </AlertDialog>
+  );
+};
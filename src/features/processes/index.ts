@@ -1,2 +1,3 @@
 export * from "./components/ProcessMonitor";
 
+export * from "./components/SuspendedExitDialog";
@@ -170,36 +170,6 @@ export const useStats = (days: number = 7) => {
   return { data, loading, error, refetch: fetchStats };
 };
 
-export const saveHistoryData = async (data: {
-  cpu?: {
-    usage: number;
-    frequency: number;
-    per_core: number[];
-  };
-  memory?: {
-    usage_percent: number;
-    used_bytes: number;
-    available_bytes: number;
-    swap_used_bytes: number;
-    swap_total_bytes: number;
-  };
-  network?: {
-    interface_name: string;
-    rx_bytes: number;
-    tx_bytes: number;
-    rx_speed: number;
-    tx_speed: number;
-  };
-  disk?: {
-    disk_name: string;
-    read_speed: number;
-    write_speed: number;
-    usage_percent: number;
-  };
-}): Promise<string> => {
-  return invoke("save_history_data", { request: data });
-};
-
 export const cleanupOldData = async (): Promise<string> => {
   return invoke("cleanup_old_data");
 };
@@ -1,12 +1,9 @@
 import { useEffect, useRef } from "react";
+import { Channel, invoke } from "@tauri-apps/api/core";
 import {
-  cpuInfo,
-  memoryInfo,
   networks,
   processes,
   disks,
-  refreshCpu,
-  refreshMemory,
   refreshProcesses,
   staticInfo,
 } from "tauri-plugin-system-info-api";
@@ -14,19 +11,14 @@ import { useSystemStore } from "@/stores/systemStore";
 import type {
   DiskInfo,
   MemoryStats,
+  MetricsTick,
+  MetricsTickMessage,
   NetworkStats,
   ProcessInfo,
   SystemInfo,
 } from "@/types/system";
 import { calculateSpeed } from "@/lib/format";
-import {
-  MEDIUM_INTERVAL_MS,
-  REALTIME_INTERVAL_MS,
-  HISTORY_SAVE_INTERVAL_MS,
-  CLEANUP_INTERVAL_MS,
-  AGGREGATE_INTERVAL_MS,
-} from "@/lib/constants";
-import { saveHistoryData, cleanupOldData, aggregateHourly } from "@/hooks/useHistoryData";
+import { MEDIUM_INTERVAL_MS, REALTIME_INTERVAL_MS } from "@/lib/constants";
 
 const mapProcessStatus = (
   status: string,
@@ -45,9 +37,6 @@ export const useSystemStats = () => {
     tx: number;
     ts: number;
   } | null>(null);
-  const lastSaveTimeRef = useRef<number>(0);
-  const lastCleanupTimeRef = useRef<number>(0);
-  const lastAggregateTimeRef = useRef<number>(0);
 
   useEffect(() => {
     let mounted = true;
@@ -65,37 +54,29 @@ export const useSystemStats = () => {
       if (mounted) setStatic({ systemInfo: sys });
     };
 
-    const tickRealtime = async () => {
-      await Promise.all([refreshCpu(), refreshMemory()]);
-
-      const [cpu, mem, net] = await Promise.all([
-        cpuInfo(),
-        memoryInfo(),
-        networks(),
-      ]);
-
-      const perCore = cpu.cpus.map((c) => c.cpu_usage);
-      const usage =
-        perCore.length === 0
-          ? 0
-          : perCore.reduce((sum, v) => sum + v, 0) / perCore.length;
-      const frequency =
-        cpu.cpus.length === 0
-          ? 0
-          : cpu.cpus.reduce((sum, c) => sum + c.frequency, 0) / cpu.cpus.length;
-
+    // CPU 与内存由后端按订阅间隔推送
+    const handleTick = (tick: MetricsTick, timestamp: number) => {
+      const record = tick.memory;
       const memory: MemoryStats = {
-        total: mem.total_memory,
-        used: mem.used_memory,
-        available: Math.max(0, mem.total_memory - mem.used_memory),
-        free: Math.max(0, mem.total_memory - mem.used_memory),
-        swap_total: mem.total_swap,
-        swap_used: mem.used_swap,
-        usage_percent:
-          mem.total_memory === 0
-            ? 0
-            : (mem.used_memory / mem.total_memory) * 100,
+        total: record.total_bytes,
+        used: record.used_bytes,
+        available: record.available_bytes,
+        free: record.free_bytes,
+        swap_total: record.swap_total_bytes,
+        swap_used: record.swap_used_bytes,
+        usage_percent: record.usage_percent,
       };
+      updateRealtime({
+        cpu: tick.cpu,
+        memory,
+        timestamp: Math.floor(timestamp / 1000),
+      });
+      pushHistory({ cpu: tick.cpu.usage, memory: memory.usage_percent });
+    };
+
+    // 网卡错误计数只有插件提供，网络仍然轮询
+    const tickRealtime = async () => {
+      const net = await networks();
 
       const interfaces = net.map((i) => ({
         name: i.interface_name,
@@ -122,8 +103,6 @@ export const useSystemStats = () => {
 
       if (mounted) {
         pushHistory({
-          cpu: usage,
-          memory: memory.usage_percent,
           network: {
             rx: (speedRx * 8) / 1_000_000,
             tx: (speedTx * 8) / 1_000_000,
@@ -144,62 +123,7 @@ export const useSystemStats = () => {
       };
 
       if (!mounted) return;
-      updateRealtime({
-        cpu: {
-          usage,
-          frequency,
-          core_count: cpu.cpu_count,
-          per_core: perCore,
-          load_avg: [0, 0, 0],
-        },
-        memory,
-        network,
-        timestamp: Math.floor(now / 1000),
-      });
-
-      const lastSave = lastSaveTimeRef.current;
-      if (now - lastSave >= HISTORY_SAVE_INTERVAL_MS) {
-        lastSaveTimeRef.current = now;
-        try {
-          await saveHistoryData({
-            cpu: {
-              usage,
-              frequency,
-              per_core: perCore,
-            },
-            memory: {
-              usage_percent: memory.usage_percent,
-              used_bytes: memory.used,
-              available_bytes: memory.available,
-              swap_used_bytes: memory.swap_used,
-              swap_total_bytes: memory.swap_total,
-            },
-            network: {
-              interface_name: interfaces[0]?.name ?? "unknown",
-              rx_bytes: totals.rx,
-              tx_bytes: totals.tx,
-              rx_speed: speedRx,
-              tx_speed: speedTx,
-            },
-          });
-        } catch {}
-      }
-
-      const lastCleanup = lastCleanupTimeRef.current;
-      if (now - lastCleanup >= CLEANUP_INTERVAL_MS) {
-        lastCleanupTimeRef.current = now;
-        try {
-          await cleanupOldData();
-        } catch {}
-      }
-
-      const lastAggregate = lastAggregateTimeRef.current;
-      if (now - lastAggregate >= AGGREGATE_INTERVAL_MS) {
-        lastAggregateTimeRef.current = now;
-        try {
-          await aggregateHourly();
-        } catch {}
-      }
+      updateRealtime({ network });
     };
 
     const tickMedium = async () => {
@@ -247,6 +171,25 @@ export const useSystemStats = () => {
       }
     };
 
+    // 卸载或页面刷新后退订；刷新时来不及退订的由后端在发送失败时清理
+    let subscriptionId: number | null = null;
+    const channel = new Channel<MetricsTickMessage>();
+    channel.onmessage = (message) => {
+      if (mounted) handleTick(message.data, message.timestamp);
+    };
+    invoke<number>("subscribe_metrics", {
+      intervalMs: REALTIME_INTERVAL_MS,
+      onTick: channel,
+    })
+      .then((id) => {
+        if (mounted) {
+          subscriptionId = id;
+        } else {
+          void invoke("unsubscribe_metrics", { subscriptionId: id }).catch(() => {});
+        }
+      })
+      .catch(() => {});
+
     safe(loadStatic);
     safe(tickRealtime);
     safe(tickMedium);
@@ -262,8 +205,11 @@ export const useSystemStats = () => {
 
     return () => {
       mounted = false;
+      if (subscriptionId !== null) {
+        void invoke("unsubscribe_metrics", { subscriptionId }).catch(() => {});
+      }
       window.clearInterval(realtimeTimer);
       window.clearInterval(mediumTimer);
     };
-  }, [setStatic, updateMedium, updateRealtime]);
+  }, [pushHistory, setStatic, updateMedium, updateRealtime]);
 };
\ No newline at end of file
@@ -34,8 +34,10 @@ export interface DiskHistoryRecord {
 export interface HourlyStats {
   hour_timestamp: number;
   avg_cpu_usage: number;
+  min_cpu_usage: number | null; // 迁移前的汇总行为 null
   max_cpu_usage: number;
   avg_memory_usage: number;
+  min_memory_usage: number | null;
   max_memory_usage: number;
   total_rx_bytes: number;
   total_tx_bytes: number;
@@ -44,8 +46,10 @@ export interface HourlyStats {
 export interface DailyStats {
   day_timestamp: number;
   avg_cpu_usage: number;
+  min_cpu_usage: number | null; // 迁移前的汇总行为 null
   max_cpu_usage: number;
   avg_memory_usage: number;
+  min_memory_usage: number | null;
   max_memory_usage: number;
   total_rx_bytes: number;
   total_tx_bytes: number;
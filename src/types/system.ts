@@ -1,3 +1,9 @@
+import type {
+  DiskHistoryRecord,
+  MemoryHistoryRecord,
+  NetworkHistoryRecord,
+} from "./history";
+
 // 基础类型
 export type ByteSize = number; // 字节，前端需格式化显示
 export type Percentage = number; // 0-100
@@ -18,6 +24,18 @@ export interface CpuStats {
   core_count: number;
   per_core: Percentage[];
   load_avg: [number, number, number]; // 1, 5, 15 min
+  running_tasks: number | null;
+  total_tasks: number | null;
+  frequencies: CoreFrequency[]; // 没有 cpufreq 时为空
+}
+
+// 单位 MHz
+export interface CoreFrequency {
+  core: number;
+  current: number;
+  min: number;
+  max: number;
+  governor: string | null;
 }
 
 // 内存相关
@@ -73,6 +91,14 @@ export interface ProcessInfo {
   command?: string;
 }
 
+// 由 dashsys 暂停（SIGSTOP）且仍未恢复的进程
+export interface SuspendedProcess {
+  pid: number;
+  name: string;
+  root: number;
+  suspended_at: Timestamp;
+}
+
 // 系统静态信息
 export interface SystemInfo {
   hostname: string;
@@ -106,3 +132,23 @@ export interface DetailedSystemInfo {
   hotfixes: string[];
   networkAdapters: NetworkAdapterInfo[];
 }
+
+// subscribe_metrics 通过 Channel 推送的数据，同时也是 metrics://tick 事件的负载；timestamp 为毫秒
+export interface MetricsTickMessage {
+  data: MetricsTick;
+  timestamp: number;
+}
+
+export interface MetricsTick {
+  subscription_id: number;
+  cpu: CpuStats;
+  memory: MemorySnapshot;
+  networks: NetworkHistoryRecord[];
+  disks: DiskHistoryRecord[];
+}
+
+// 实时推送的内存数据，比历史记录多出总量与空闲量
+export interface MemorySnapshot extends MemoryHistoryRecord {
+  total_bytes: ByteSize;
+  free_bytes: ByteSize;
+}